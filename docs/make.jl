@@ -17,25 +17,51 @@ makedocs(
         ],
         "Library" => [
             "lib/pangraph.md",
+            "lib/accumulation.md",
             "lib/align.md",
+            "lib/alignments.md",
+            "lib/annotation.md",
+            "lib/bed.md",
+            "lib/binary.md",
             "lib/block.md",
             "lib/edge.md",
+            "lib/figure.md",
             "lib/graph.md",
             "lib/gfa.md",
+            "lib/marginal.md",
             "lib/mash.md",
             "lib/minimap.md",
             "lib/mmseqs.md",
             "lib/node.md",
             "lib/path.md",
+            "lib/phylogeny.md",
+            "lib/presence.md",
+            "lib/schema.md",
+            "lib/server.md",
             "lib/simulate.md",
+            "lib/summary.md",
+            "lib/vcf.md",
+            "lib/viewer.md",
             "lib/utility.md",
         ],
         "Command Line" => [
+            "cli/add.md",
+            "cli/annotate.md",
             "cli/build.md",
             "cli/export.md",
+            "cli/extract.md",
             "cli/generate.md",
+            "cli/growth.md",
+            "cli/liftover.md",
             "cli/marginalize.md",
+            "cli/merge.md",
+            "cli/plot.md",
             "cli/polish.md",
+            "cli/sequences.md",
+            "cli/serve.md",
+            "cli/stats.md",
+            "cli/trees.md",
+            "cli/validate.md",
             "cli/version.md",
         ],
         "Development" => [
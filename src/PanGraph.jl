@@ -4,6 +4,9 @@ ENV["PYTHON"] = ""
 
 using GZip
 using Rematch
+using SHA
+
+import JSON
 using Random: seed!
 
 # ------------------------------------------------------------------------
@@ -107,19 +110,76 @@ function open(func, path, args...)
     return Base.open(func, path, args...)
 end
 
+# NOTE: the format of a pangraph is detected from its leading byte
+#       json starts with an object, binary with its magic number and gfa with a header or segment record
+#       files with a gfa extension are always parsed as gfa, e.g. if they start with a comment
+function detect(io; gfa=false)
+   eof(io) && error("empty pangraph input")
+   byte = peek(io)
+   if byte == Graphs.Binary.MAGIC[1]
+       unmarshal_binary(io)
+   elseif Char(byte) == '{' || isspace(Char(byte))
+       unmarshal(io)
+   elseif gfa || Char(byte) ∈ ('H', 'S')
+       unmarshal_gfa(io)
+   else
+       error("unrecognized pangraph format: expected json, binary or gfa")
+   end
+end
+
 function load(path, cmd)
    if path === nothing
-       unmarshal(stdin)
+       detect(stdin)
    elseif length(path) == 1
        path = path[1]
        !isfile(path) && error("file '$(path)' not found")
-       open(unmarshal, path)
+       open((io) -> detect(io; gfa=occursin(r"\.gfa(\.gz)?$", path)), path)
    else
        usage(cmd)
        return 2
    end
 end
 
+function outputformat(cmd)
+   fmt = arg(cmd, "-f")
+   fmt ∈ ("json", "binary") || panic("unrecognized pangraph format '$(fmt)'\n\trecognized options: [json, binary]\n")
+   return Symbol(fmt)
+end
+
+extension(fmt) = fmt == :binary ? "pgb" : String(fmt)
+
+# NOTE: path separators are not allowed within file names
+filename(name) = replace(name, r"[/\\]" => "_")
+
+# NOTE: checksums are computed over the raw bytes; streams such as stdin cannot be reread and are skipped
+checksum(path) = isfile(path) ? bytes2hex(Base.open(sha256, path)) : nothing
+
+"""
+    provenance!(G::Graph, cmd, files; history=[])
+
+Record the pangraph version, the subcommand `cmd` with all its parameters and the checksums of all input `files` within the metadata of graph `G`.
+`history` holds the metadata of the graphs `G` was derived from, if any.
+"""
+function provenance!(G::Graph, cmd, files; history=[])
+    metadata = Dict{String,Any}(
+        "pangraph"   => PANGRAPH_VERSION,
+        "command"    => cmd.cmd,
+        "parameters" => Dict(String(lstrip(a.flag.long, '-')) => a.value for a in cmd.arg if !ismissing(a.flag.long)),
+        "inputs"     => [Dict("path" => file, "sha256" => checksum(file)) for file in files],
+        "history"    => [h for h in history if !isempty(h)],
+    )
+
+    empty!(G.metadata)
+    merge!(G.metadata, metadata)
+end
+
+# NOTE: (re)alignment changes consensus coordinates, such that the features attached to affected blocks are dropped
+attached(G::Graph) = Set((a.isolate, a.id, a.part) for fs in values(G.annotation) for a in fs)
+function warnfeatures(before, G::Graph)
+    lost = length(setdiff(before, attached(G)))
+    lost > 0 && @warn "$(lost) feature parts attached to realigned blocks were dropped, rerun annotate to transfer them again"
+end
+
 # ------------------------------------------------------------------------
 # subcommands and arguments
 
@@ -136,6 +196,18 @@ include("version.jl")
 include("polish.jl")
 include("marginalize.jl")
 include("export.jl")
+include("add.jl")
+include("merge.jl")
+include("validate.jl")
+include("sequences.jl")
+include("extract.jl")
+include("liftover.jl")
+include("annotate.jl")
+include("serve.jl")
+include("plot.jl")
+include("stats.jl")
+include("growth.jl")
+include("trees.jl")
 
 Dispatch = Command(
     "pangraph",
@@ -150,6 +222,18 @@ Dispatch = Command(
      Polish,
      Marginalize,
      Export,
+     Add,
+     Merge,
+     Validate,
+     Sequences,
+     Extract,
+     Liftover,
+     Annotate,
+     Serve,
+     Plot,
+     Stats,
+     Growth,
+     Trees,
     ],
 )
 
@@ -0,0 +1,100 @@
+module Accumulation
+
+using Random: MersenneTwister, randperm
+
+import ..Graphs:
+    Graph, Block,
+    count_isolates
+
+export curves, heaps, write_curves
+
+"""
+    curves(G::Graph, n; seed=0)
+
+Return the pangenome and core genome accumulation curves of graph `G` for `n` random orderings of its isolates, drawn from a generator seeded by `seed`.
+Isolates are added one at a time, without realignment: the subgraph of the first `k` isolates contains all blocks found in any of them, as done by `keeponly!`.
+
+Return an array with one named tuple per ordering and number of isolates `k`, holding the `ordering` index, `k`, the name of the last added `isolate`,
+the `pangenome` length, i.e. the total consensus length of all blocks found so far, and the `core` length, i.e. the total consensus length of blocks found in all `k` isolates.
+"""
+function curves(G::Graph, n; seed=0)
+    n ≥ 1 || error("number of orderings must be positive")
+
+    names  = sort(collect(keys(G.sequence)))
+    blocks = Dict(name => Set{Block}() for name in names)
+    for (b, count) in count_isolates(values(G.sequence)), name in keys(count)
+        push!(blocks[name], b)
+    end
+
+    rng    = MersenneTwister(seed)
+    result = []
+    for ordering in 1:n
+        seen = Set{Block}()
+        core = Set{Block}()
+        pan  = 0
+        for (k, i) in enumerate(randperm(rng, length(names)))
+            name = names[i]
+            for b in blocks[name]
+                b ∈ seen && continue
+                push!(seen, b)
+                pan += length(b)
+            end
+            core = k == 1 ? copy(blocks[name]) : intersect!(core, blocks[name])
+
+            push!(result, (
+                ordering  = ordering,
+                isolates  = k,
+                isolate   = name,
+                pangenome = pan,
+                core      = sum((length(b) for b in core); init=0),
+            ))
+        end
+    end
+
+    return result
+end
+
+"""
+    heaps(curve)
+
+Fit Heaps' law, `Δ = κ N^(-α)`, to the average length of new sequence `Δ` contributed by the `N`th isolate of the accumulation curves `curve`, as returned by `curves`.
+The fit is a least squares regression in log-log space over `N ≥ 2`, excluding points without new sequence.
+Return the prefactor `κ`, the exponent `α` and whether the pangenome is `open`, i.e. `α ≤ 1`.
+Return `nothing` if less than two points are available.
+"""
+function heaps(curve)
+    # mean increment of the pangenome length for each number of isolates
+    previous = Dict((c.ordering, c.isolates) => c.pangenome for c in curve)
+    Δ = Dict{Int,Array{Float64,1}}()
+    for c in curve
+        c.isolates ≥ 2 || continue
+        push!(get!(Δ, c.isolates, Float64[]), c.pangenome - previous[(c.ordering, c.isolates-1)])
+    end
+
+    points = [(log(N), log(sum(δ)/length(δ))) for (N, δ) in Δ if sum(δ) > 0]
+    length(points) ≥ 2 || return nothing
+
+    x̄ = sum(first.(points))/length(points)
+    ȳ = sum(last.(points))/length(points)
+    sxx = sum((x-x̄)^2 for (x, _) in points)
+    sxx > 0 || return nothing
+    slope = sum((x-x̄)*(y-ȳ) for (x, y) in points)/sxx
+
+    α = -slope
+    κ = exp(ȳ - slope*x̄)
+    return (kappa=κ, alpha=α, open=α ≤ 1)
+end
+
+"""
+    write_curves(io::IO, curve)
+
+Output the accumulation curves `curve`, as returned by `curves`, to IO stream `io` as a tab-separated table with one row per ordering and number of isolates.
+"""
+function write_curves(io::IO, curve)
+    write(io, join(["ordering", "isolates", "isolate", "pangenome", "core"], '\t'), '\n')
+    for c in curve
+        write(io, join([c.ordering, c.isolates, c.isolate, c.pangenome, c.core], '\t'), '\n')
+    end
+end
+
+end
@@ -0,0 +1,138 @@
+Add = Command(
+   "add",
+   "pangraph add <options> [pangraph.json] [arguments]",
+   "aligns new genomes onto an existing multiple sequence alignment graph",
+   """one pangraph file (json, binary or gfa), followed by one or more fasta files.
+      files can be optionally gzipped.
+      multiple records within one file are treated as seperate genomes.
+      alignment parameters should match those used to build the pangraph.""",
+   [
+    Arg(
+        Int,
+        "minimum length",
+        (short="-l", long="--len"),
+        "minimum block size for alignment graph (in nucleotides)",
+        100,
+    ),
+    Arg(
+        Float64,
+        "block junction cost",
+        (short="-a", long="--alpha"),
+        "energy cost for introducing junction due to alignment merger",
+        100,
+    ),
+    Arg(
+        Float64,
+        "block diversity cost",
+        (short="-b", long="--beta"),
+        "energy cost for interblock diversity due to alignment merger",
+        10,
+    ),
+    Arg(
+        Bool,
+        "circular genomes",
+        (short="-c", long="--circular"),
+        "toggle if input genomes are circular",
+        false,
+    ),
+    Arg(
+        Bool,
+        "enforce uppercase",
+        (short="-u", long="--upper-case"),
+        "transforms all sequence to upper case",
+        false,
+    ),
+    Arg(
+        String,
+        "pairwise sensitivity",
+        (short="-s", long="--sensitivity"),
+        "used to set pairwise alignment sensitivity\n\trecognized options: [5, 10, 20]",
+        "10",
+    ),
+    Arg(
+        Int,
+        "maximum self maps",
+        (short="-x", long="--max-self-map"),
+        "maximum number of self mappings to consider per pairwise graph merger",
+        100,
+    ),
+    Arg(
+        String,
+        "distance calculator",
+        (short="-d", long="--distance-backend"),
+        "backend to use to estimate pairwise distance for guide tree of new genomes\n\trecognized options: [native, mash]",
+        "native",
+    ),
+    Arg(
+        String,
+        "alignment kernel",
+        (short="-k", long="--alignment-kernel"),
+        "backend to use for pairwise genome alignment\n\trecognized options: [minimap2, mmseqs]",
+        "minimap2",
+    ),
+    Arg(
+        Int,
+        "k-mer length",
+        (short="-K", long="--kmer-length"),
+        "kmer length, only used for mmseqs2 alignment kernel. If not specified will use mmseqs default.",
+        0,
+    ),
+    Arg(
+        String,
+        "output format",
+        (short="-f", long="--format"),
+        "format of the output pangraph\n\trecognized options: [json, binary]",
+        "json",
+    ),
+   ],
+
+   function(args)
+       files = parse(Add, args)
+       if files === nothing || length(files) < 2
+           usage(Add)
+           return 2
+       end
+
+       graph   = load(files[1:1], Add)
+       history = [copy(graph.metadata)]
+
+       minblock  = arg(Add, "-l")
+       circular  = arg(Add, "-c")
+       uppercase = arg(Add, "-u")
+       fmt       = outputformat(Add)
+
+       energy  = alignment_energy(minblock, arg(Add, "-a"), arg(Add, "-b"))
+       maxiter = arg(Add, "-x")
+
+       sensitivity = alignment_sensitivity(arg(Add, "-s"))
+       if sensitivity === nothing
+           usage(Add)
+           exit(1)
+       end
+
+       compare = distance_backend(arg(Add, "-d"))
+       if compare === nothing
+           usage(Add)
+           exit(1)
+       end
+
+       aligner = alignment_kernel(arg(Add, "-k"), minblock, sensitivity, arg(Add, "-K"))
+
+       singletons(io) = graphs(io; circular=circular, upper=uppercase)
+       isolates = [G for file in files[2:end] for G ∈ open(singletons,file)]
+
+       before = attached(graph)
+       graph  = Graphs.add(aligner, graph, isolates...;
+            compare     = compare,
+            energy      = energy,
+            minblock    = minblock,
+            maxiter     = maxiter,
+       )
+       finalize!(graph)
+       warnfeatures(before, graph)
+       provenance!(graph, Add, files; history=history)
+
+       marshal(stdout, graph; fmt=fmt)
+       return 0
+   end
+)
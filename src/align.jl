@@ -13,7 +13,7 @@ using ..Nodes
 using ..Graphs
 using ..Mash
 
-export align
+export align, add, parse_newick, newick, guidetree, checkguide
 
 # ------------------------------------------------------------------------
 # helper functions
@@ -94,6 +94,103 @@ function Clade(distance, names; algo=:nj)
     end
 end
 
+"""
+	parse_newick(newick::AbstractString)
+
+Generate a tree from its description `newick` in newick format, as written by `newick`.
+Labels may be single-quoted, with quotes within doubled. Branch lengths, support values, internal node labels and comments are ignored.
+Multifurcations are resolved by successively joining children from left to right,
+i.e. an unrooted tree given with a trifurcation at its root is rooted arbitrarily.
+"""
+function parse_newick(newick::AbstractString)
+    str = collect(newick)
+    pos = 1
+
+    current() = pos ≤ length(str) ? str[pos] : nothing
+    # skip blanks and comments
+    function blank()
+        while pos ≤ length(str) && (isspace(str[pos]) || str[pos] == '[')
+            if str[pos] == '['
+                while pos ≤ length(str) && str[pos] != ']'
+                    pos += 1
+                end
+            end
+            pos += 1
+        end
+    end
+
+    function label()
+        blank()
+        name = if current() == '\''
+            buf = IOBuffer()
+            pos += 1
+            while true
+                pos ≤ length(str) || error("unterminated quoted label in newick tree")
+                c = str[pos]
+                pos += 1
+                if c == '\''
+                    current() == '\'' || break
+                    pos += 1
+                end
+                write(buf, c)
+            end
+            String(take!(buf))
+        else
+            start = pos
+            while pos ≤ length(str) && str[pos] ∉ "(),:;["
+                pos += 1
+            end
+            String(strip(String(str[start:pos-1])))
+        end
+
+        # branch length
+        blank()
+        if current() == ':'
+            while pos ≤ length(str) && str[pos] ∉ "(),;["
+                pos += 1
+            end
+        end
+
+        return name
+    end
+
+    function clade()
+        blank()
+        if current() != '('
+            leaf = label()
+            isempty(leaf) && error("unnamed leaf in newick tree")
+            return Clade(leaf)
+        end
+
+        pos += 1
+        children = [clade()]
+        blank()
+        while current() == ','
+            pos += 1
+            push!(children, clade())
+            blank()
+        end
+        current() == ')' || error("unbalanced parenthesis in newick tree")
+        pos += 1
+        label()
+
+        node = children[1]
+        for child in children[2:end]
+            node = Clade(node, child)
+        end
+        return node
+    end
+
+    root = clade()
+    blank()
+    current() == ';' || error("newick tree must be terminated by ';'")
+    pos += 1
+    blank()
+    current() === nothing || error("unexpected characters after the end of the newick tree")
+
+    return root
+end
+
 """
 	nj(distance, names)
 
@@ -183,10 +280,13 @@ Return if Clade `c` is a terminal node, i.e. a leaf.
 """
 isleaf(c::Clade) = isnothing(c.left) && isnothing(c.right)
 
+# NOTE: labels holding newick punctuation or blanks are single-quoted, quotes within are doubled
+quoted(name) = occursin(r"[\s(),:;'\[\]]", name) ? "'$(replace(name, "'" => "''"))'" : name
+
 # serialization to newick format
 function Base.show(io::IO, c::Clade) 
     if isleaf(c)
-        print(io, c.name)
+        print(io, quoted(c.name))
     else
         print(io, "(")
         show(io, c.left)
@@ -196,6 +296,20 @@ function Base.show(io::IO, c::Clade)
     end
 end
 
+"""
+	newick(c::Clade)
+
+Return the topology of the tree rooted at `c` in newick format, as read by `parse_newick`.
+"""
+newick(c::Clade) = "$(c);"
+
+"""
+	copy(c::Clade)
+
+Return a copy of the topology of the tree rooted at `c`, with new, empty message channels.
+"""
+Base.copy(c::Clade) = isleaf(c) ? Clade(c.name) : Clade(copy(c.left), copy(c.right))
+
 """
 	leaves(root::Clade)
 
@@ -317,6 +431,31 @@ function ordering(compare, Gs...)
     return Clade(distance, names; algo=:nj)
 end
 
+"""
+	guidetree(compare, Gs...)
+
+Return the balanced guide tree used to align a collection of singleton graphs `Gs`, see `ordering` and `balance`.
+"""
+guidetree(compare, Gs...) = ordering(compare, Gs...) |> balance
+
+"""
+	checkguide(tree::Clade, names)
+
+Throw an error unless the leaves of guide tree `tree` are named exactly by the isolate names `names`, each found once.
+"""
+function checkguide(tree::Clade, names)
+    tips = [leaf.name for leaf in leaves(tree)]
+
+    duplicate = unique(filter(name -> count(==(name), tips) > 1, tips))
+    isempty(duplicate) || error("guide tree leaves found more than once: $(join(duplicate, ", "))")
+
+    absent = setdiff(names, tips)
+    isempty(absent) || error("isolates not found in guide tree: $(join(absent, ", "))")
+
+    unknown = setdiff(tips, names)
+    isempty(unknown) || error("guide tree leaves not found among isolates: $(join(unknown, ", "))")
+end
+
 # ------------------------------------------------------------------------
 # align functions
 
@@ -436,6 +575,8 @@ function align_self(G₁::Graph, energy::Function, minblock::Int, aligner::Funct
         G₀ = Graph(
             blocks,
             G₀.sequence,
+            G₀.metadata,
+            G₀.annotation,
         )
         detransitive!(G₀)
         purge!(G₀)
@@ -564,9 +705,12 @@ function align_pair(G₁::Graph, G₂::Graph, energy::Function, minblock::Int, a
     merge!(blocks, G₁.block)
     merge!(blocks, G₂.block)
 
+    # NOTE: features of blocks merged by the alignment are dropped by prune!
     G = Graph(
         blocks,
         sequence,
+        Dict{String,Any}(),
+        merge(G₁.annotation, G₂.annotation),
     )
 
     detransitive!(G)
@@ -579,10 +723,10 @@ end
 # TODO: the associative array is a bit hacky...
 #       can we push it directly into the channel?
 """
-	align(aligner::Function, Gs::Graph...; compare=Mash.distance, energy=(hit)->(-Inf), minblock=100, reference=nothing, maxiter=100)
+	align(aligner::Function, Gs::Graph...; compare=Mash.distance, energy=(hit)->(-Inf), minblock=100, reference=nothing, maxiter=100, guide=nothing)
 
 Aligns a collection of graphs `Gs` using the specified `aligner` function to recover hits.
-Graphs are aligned following an internal guide tree, generated using kmer distance.
+Graphs are aligned following an internal guide tree, generated using kmer distance, see `guidetree`.
 
 `energy` is to be a function that takes an alignment between two blocks and produces a score.
 The _lower_ the score, the _better_ the alignment. Only negative energies are considered.
@@ -591,8 +735,10 @@ The _lower_ the score, the _better_ the alignment. Only negative energies are co
 `maxiter` is maximum number of duplications that will be considered during this alignment.
 
 `compare` is the function to be used to generate pairwise distances that generate the internal guide tree.
+`guide`, if given, is the guide tree used instead, as returned by `parse_newick`. Its leaves must be named by the isolates of `Gs`.
+It is used as is, i.e. it is not balanced, and is left untouched.
 """
-function align(aligner::Function, Gs::Graph...; compare=Mash.distance, energy=(hit)->(-Inf), minblock=100, reference=nothing, maxiter=100)
+function align(aligner::Function, Gs::Graph...; compare=Mash.distance, energy=(hit)->(-Inf), minblock=100, reference=nothing, maxiter=100, guide=nothing)
     function verify(graph, msg="")
         if reference !== nothing
             for (name,path) ∈ graph.sequence
@@ -638,8 +784,13 @@ function align(aligner::Function, Gs::Graph...; compare=Mash.distance, energy=(h
         graph
     end
 
-    log("--> ordering")
-    tree = ordering(compare, Gs...) |> balance
+    tree = if guide === nothing
+        log("--> ordering")
+        guidetree(compare, Gs...)
+    else
+        checkguide(guide, [collect(keys(G.sequence))[1] for G in Gs])
+        copy(guide)
+    end
     log("--> tree: ", tree)
 
     meter = Progress(n_inner_nodes(tree); desc="alignment progress", output=stderr)
@@ -698,6 +849,65 @@ function align(aligner::Function, Gs::Graph...; compare=Mash.distance, energy=(h
     return G
 end
 
+"""
+	add(aligner::Function, G::Graph, Gs::Graph...; compare=Mash.distance, energy=(hit)->(-Inf), minblock=100, maxiter=100)
+
+Aligns a collection of singleton graphs `Gs` onto an existing graph `G` using the specified `aligner` function to recover hits.
+The new genomes are first aligned amongst themselves following an internal guide tree.
+The result is then merged into `G` by a single pairwise alignment, i.e. `G` is not realigned.
+Blocks of `G` that share no homology with the new genomes retain their `uuid`.
+
+`energy`, `minblock`, `maxiter` and `compare` are interpreted as in `align`.
+"""
+function add(aligner::Function, G::Graph, Gs::Graph...; compare=Mash.distance, energy=(hit)->(-Inf), minblock=100, maxiter=100)
+    length(Gs) > 0 || return G
+
+    # NOTE: collisions with isolates of `G` are caught by merge
+    names = [name for G₀ in Gs for name in keys(G₀.sequence)]
+    length(names) == length(Set(names)) || error("new isolates must have unique names")
+
+    G₀ = if length(Gs) > 1
+        align(aligner, Gs...; compare=compare, energy=energy, minblock=minblock, maxiter=maxiter)
+    else
+        Gs[1]
+    end
+
+    log("--> adding $(length(names)) isolate(s) to graph")
+    return merge(aligner, G, G₀; energy=energy, minblock=minblock, maxiter=maxiter)
+end
+
+"""
+	merge(aligner::Function, G::Graph, Gs::Graph...; energy=(hit)->(-Inf), minblock=100, maxiter=100)
+
+Merges independently built graphs `G` and `Gs` into one graph using the specified `aligner` function to recover hits.
+Only the consensus sequences of blocks are aligned, i.e. the graphs are not rebuilt from their genomes.
+Graphs are merged sequentially, each merger being followed by a self alignment.
+Isolate names must be unique across all graphs.
+
+`energy`, `minblock` and `maxiter` are interpreted as in `align`.
+"""
+function Base.merge(aligner::Function, G::Graph, Gs::Graph...; energy=(hit)->(-Inf), minblock=100, maxiter=100)
+    names = [name for G₀ in (G, Gs...) for name in keys(G₀.sequence)]
+    if length(names) != length(Set(names))
+        seen = Set{String}()
+        for name in names
+            name ∈ seen && error("isolate '$(name)' is contained in more than one graph")
+            push!(seen, name)
+        end
+    end
+
+    uuids = [uuid for G₀ in (G, Gs...) for uuid in keys(G₀.block)]
+    length(uuids) == length(Set(uuids)) || error("block identifiers collide between graphs")
+
+    for G₀ in Gs
+        log("--> merging $(length(G₀.sequence)) isolate(s) into graph")
+        G = align_pair(G, G₀, energy, minblock, aligner, identity, false)
+        G = align_self(G, energy, minblock, aligner, identity, false; maxiter=maxiter)
+    end
+
+    return G
+end
+
 # ------------------------------------------------------------------------
 # testing
 
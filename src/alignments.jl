@@ -0,0 +1,216 @@
+module Alignments
+
+using Rematch
+
+import ..Graphs:
+    Graph, Block,
+    alignment, count_isolates, write_fasta, genomelength, copyindex
+
+export core, coreblocks, write_alignment
+export loci, write_block, write_maf
+
+"""
+    isolates(G::Graph)
+
+Return the name of the isolate each node of graph `G` belongs to.
+"""
+isolates(G::Graph) = Dict(node => name for (name, path) in G.sequence for node in path.node)
+
+"""
+    coreblocks(G::Graph; threshold=1.0)
+
+Return all blocks of graph `G` that are found at most once in every isolate and in at least a fraction `threshold` of all isolates.
+Blocks are sorted by `uuid`.
+"""
+function coreblocks(G::Graph; threshold=1.0)
+    N = length(G.sequence)
+    core = [
+        block for (block, count) in count_isolates(values(G.sequence))
+        if all(c == 1 for c in values(count)) && length(count) ≥ threshold*N
+    ]
+
+    return sort(core; by=(b)->b.uuid)
+end
+
+ispolymorphic(site) = length(Set(uppercase(Char(c)) for c in site if c != UInt8('-') && c != UInt8('N') && c != UInt8('n'))) > 1
+
+"""
+    core(G::Graph; threshold=1.0, snps=false)
+
+Return the concatenated multiple sequence alignment of all core blocks of graph `G`, as defined by `coreblocks`.
+Isolates missing from a block are padded with gaps.
+If `snps` is true, only sites with at least two distinct nucleotides are retained.
+Return the sorted isolate names and the alignment, stored as one column per isolate.
+"""
+function core(G::Graph; threshold=1.0, snps=false)
+    names = sort(collect(keys(G.sequence)))
+    index = Dict(name => i for (i, name) in enumerate(names))
+    owner = isolates(G)
+
+    blocks = map(coreblocks(G; threshold=threshold)) do block
+        aln, nodes, _ = alignment(block)
+        col = fill(UInt8('-'), size(aln,1), length(names))
+        for (j, node) in enumerate(nodes)
+            col[:, index[owner[node]]] = aln[:, j]
+        end
+        col
+    end
+
+    aln = reduce(vcat, blocks; init=Array{UInt8}(undef, 0, length(names)))
+    if snps
+        aln = aln[[ispolymorphic(site) for site in eachrow(aln)], :]
+    end
+
+    return names, aln
+end
+
+"""
+    write_alignment(io::IO, names, aln; fmt=:fasta)
+
+Output the alignment `aln`, stored as one column per name in `names`, to IO stream `io`.
+`fmt` can be either `:fasta` (aligned FASTA) or `:phylip` (relaxed sequential PHYLIP).
+"""
+function write_alignment(io::IO, names, aln; fmt=:fasta)
+    @match fmt begin
+        :fasta || :fa => begin
+            for (name, seq) in zip(names, eachcol(aln))
+                write_fasta(io, name, seq)
+            end
+        end
+        :phylip || :phy => begin
+            write(io, "$(length(names)) $(size(aln,1))\n")
+            for (name, seq) in zip(names, eachcol(aln))
+                write(io, name, ' ', String(collect(seq)), '\n')
+            end
+        end
+        _ => error("$fmt not a recognized alignment format")
+    end
+end
+
+# ------------------------------------------------------------------------
+# per-block alignments
+
+"""
+    loci(G::Graph)
+
+Return the location of each node of graph `G` within its genome as a named tuple of
+the `isolate` name, the `copy` number of its block within the isolate (counted along the path),
+its 1-based `start` position, its `length` and the `total` length of the genome.
+"""
+function loci(G::Graph)
+    locus = Dict()
+    for (name, path) in G.sequence
+        total  = genomelength(path)
+        copies = copyindex(path)
+        for (i, node) in enumerate(path.node)
+            locus[node] = (
+                isolate = name,
+                copy    = copies[i],
+                start   = path.position[i],
+                length  = length(node),
+                total   = total,
+            )
+        end
+    end
+
+    return locus
+end
+
+strand(node) = node.strand ? '+' : '-'
+
+"""
+    write_block(io::IO, b::Block, locus; fmt=:fasta)
+
+Output the multiple sequence alignment of block `b` to IO stream `io`, with all rows in the orientation of the block consensus.
+`locus` maps each node to its location, as returned by `loci`.
+Rows are named `isolate#copy` and sorted by name; the strand and the 1-based, inclusive genomic interval of each row are stored alongside.
+The end of an interval is smaller than its start if it wraps around the origin of a circular genome.
+`fmt` can be either `:fasta` or `:stockholm`.
+"""
+function write_block(io::IO, b::Block, locus; fmt=:fasta)
+    aln, nodes, _ = alignment(b)
+    order = sortperm([(locus[n].isolate, locus[n].copy) for n in nodes])
+
+    name  = (n) -> "$(locus[n].isolate)#$(locus[n].copy)"
+    stop  = (n) -> mod1(locus[n].start + locus[n].length - 1, locus[n].total)
+    info  = (n) -> "strand=$(strand(n)) start=$(locus[n].start) end=$(stop(n))"
+
+    @match fmt begin
+        :fasta || :fa => begin
+            for j in order
+                write_fasta(io, "$(name(nodes[j])) $(info(nodes[j]))", aln[:,j])
+            end
+        end
+        :stockholm || :sto => begin
+            write(io, "# STOCKHOLM 1.0\n")
+            write(io, "#=GF ID $(b.uuid)\n")
+            for j in order
+                write(io, "#=GS $(name(nodes[j])) DE $(info(nodes[j]))\n")
+            end
+            width = maximum(length(name(n)) for n in nodes; init=0)
+            for j in order
+                write(io, rpad(name(nodes[j]), width), ' ', String(aln[:,j]), '\n')
+            end
+            write(io, "//\n")
+        end
+        _ => error("$fmt not a recognized block alignment format")
+    end
+end
+
+"""
+    write_maf(io::IO, G::Graph)
+
+Output the multiple sequence alignment of every block of graph `G`, sorted by `uuid`, to IO stream `io` in the Multiple Alignment Format (MAF).
+Each block is emitted as an alignment block, in the orientation of its consensus, with one `s` line per node.
+Sources are named after isolates; start positions are 0-based and relative to the strand of each line.
+Blocks that wrap around the origin of a circular genome are split into consecutive alignment blocks at the origin.
+"""
+function write_maf(io::IO, G::Graph)
+    write(io, "##maf version=1 scoring=none\n\n")
+
+    locus = loci(G)
+    gap   = UInt8('-')
+    for b in sort(collect(values(G.block)); by=(b)->b.uuid)
+        aln, nodes, _ = alignment(b)
+        order = sortperm([(locus[n].isolate, locus[n].copy) for n in nodes])
+
+        # start of each row, 0-based and relative to its strand
+        start = map(nodes) do n
+            l = locus[n]
+            n.strand ? l.start - 1 : mod(l.total - (l.start - 1) - l.length, l.total)
+        end
+
+        # NOTE: rows are cut at the column that crosses the origin of their genome
+        cuts = Set{Int}([0, size(aln,1)])
+        for (j, n) in enumerate(nodes)
+            l = locus[n]
+            start[j] + l.length > l.total || continue
+            nuc = cumsum(aln[:,j] .!= gap)
+            push!(cuts, findfirst(==(l.total - start[j]), nuc))
+        end
+        cuts = sort(collect(cuts))
+
+        for (c₁, c₂) in zip(cuts[1:end-1], cuts[2:end])
+            lines = String[]
+            for j in order
+                n, l = nodes[j], locus[nodes[j]]
+                before = count(!=(gap), view(aln, 1:c₁, j))
+                len    = count(!=(gap), view(aln, (c₁+1):c₂, j))
+                len > 0 || continue
+
+                push!(lines, join([
+                    "s", l.isolate, mod(start[j] + before, l.total), len, strand(n), l.total, String(aln[(c₁+1):c₂, j])
+                ], ' '))
+            end
+            isempty(lines) && continue
+
+            write(io, "a\n")
+            for line in lines
+                write(io, line, '\n')
+            end
+            write(io, '\n')
+        end
+    end
+end
+
+end
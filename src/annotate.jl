@@ -0,0 +1,84 @@
+Annotate = Command(
+   "annotate",
+   "pangraph annotate <options> [arguments]",
+   "attaches GFF3 features to the blocks of a multiple sequence alignment graph and transfers them to other isolates",
+   """zero or one pangraph file (json, binary or gfa)
+      if no file, reads from stdin
+      stream can be optionally gzipped.""",
+   [
+    Arg(
+        String,
+        "annotation files",
+        (short="-g", long="--gff"),
+        "GFF3 files to attach, optionally gzipped. sequence identifiers must match isolate names\n\tcomma seperated list, no spaces",
+        "",
+    ),
+    Arg(
+        String,
+        "output path",
+        (short="-o", long="--output-path"),
+        "path to directory where the features transferred to each unannotated isolate are stored as GFF3\n\tif empty, no features are transferred",
+        "",
+    ),
+    Arg(
+        String,
+        "output format",
+        (short="-f", long="--format"),
+        "format of the output pangraph\n\trecognized options: [json, binary]",
+        "json",
+    ),
+   ],
+
+   function(args)
+       path = parse(Annotate, args)
+       path = if (path === nothing || length(path) == 0)
+           nothing
+       elseif length(path) == 1
+           path
+       else
+           usage(Annotate)
+           return 2
+       end
+
+       files = arg(Annotate, "-g")
+       if length(files) == 0
+           usage(Annotate)
+           return 2
+       end
+       files = split(files, ',')
+
+       fmt   = outputformat(Annotate)
+       graph = load(path, Annotate)
+
+       features = []
+       for file in files
+           !isfile(file) && error("file '$(file)' not found")
+           append!(features, open(Graphs.Annotations.readgff, file))
+       end
+
+       sources = unique(f.seqid for f in features)
+       for name in sources
+           name ∈ keys(graph.sequence) || @warn "sequence '$(name)' of the annotation not found in pangraph, its features are skipped"
+       end
+
+       history = [copy(graph.metadata)]
+       Graphs.Annotations.annotate!(graph, features)
+       provenance!(graph, Annotate, [path === nothing ? [] : path; files]; history=history)
+
+       output = arg(Annotate, "-o")
+       if length(output) > 0
+           isdir(output) || mkpath(output)
+
+           records = Graphs.Annotations.project(graph; exclude=sources)
+           for (name, record) in records
+               L = Graphs.genomelength(graph.sequence[name])
+               Base.open("$(output)/$(filename(name)).gff3", "w") do io
+                   Graphs.Annotations.write_gff(io, name, L, record; circular=graph.sequence[name].circular)
+               end
+           end
+       end
+
+       marshal(stdout, graph; fmt=fmt)
+       return 0
+   end
+)
@@ -0,0 +1,246 @@
+module Annotations
+
+import ..Graphs:
+    Graph, Block, Node, Path, Annotation,
+    alignment, flip, genomelength
+
+import ..Regions: intervals, overlap, consensus, columns
+
+export readgff, annotate!, project, write_gff
+
+# ------------------------------------------------------------------------
+# gff3 input
+
+"""
+    readgff(io::IO)
+
+Parse the features of the GFF3 formatted input stream `io`.
+Directives, comments and `region` features are skipped; parsing stops at an embedded `##FASTA` section.
+Features without an `ID` attribute are identified by their `type` and interval.
+Return an array of named tuples, one per feature.
+"""
+function readgff(io::IO)
+    features = []
+    for (n, line) in enumerate(eachline(io))
+        startswith(line, "##FASTA") && break
+        (isempty(strip(line)) || startswith(line, '#')) && continue
+
+        field = split(line, '\t')
+        length(field) == 9 || error("gff line $(n): expected 9 tab-separated columns, found $(length(field))")
+        field[3] == "region" && continue
+
+        start, stop = tryparse(Int, field[4]), tryparse(Int, field[5])
+        (start === nothing || stop === nothing || start > stop) && error("gff line $(n): invalid interval $(field[4])-$(field[5])")
+        field[7] ∈ ("+", "-", ".", "?") || error("gff line $(n): invalid strand '$(field[7])'")
+
+        attributes = field[9] == "." ? "" : String(field[9])
+        id = match(r"(?:^|;)ID=([^;]*)", attributes)
+
+        push!(features, (
+            seqid      = String(field[1]),
+            source     = String(field[2]),
+            type       = String(field[3]),
+            start      = start,
+            stop       = stop,
+            strand     = field[7] == "?" ? '.' : field[7][1],
+            phase      = String(field[8]),
+            attributes = attributes,
+            id         = id === nothing ? "$(field[3]):$(field[1]):$(start)-$(stop)" : String(id[1]),
+        ))
+    end
+
+    return features
+end
+
+# ------------------------------------------------------------------------
+# projection onto blocks
+
+"""
+    annotate!(G::Graph, features)
+
+Attach `features`, as returned by `readgff`, to the blocks of graph `G`.
+The sequence identifier of each feature must name an isolate of `G`; coordinates refer to the original rotation of circular genomes.
+Each feature is stored once per block it overlaps, in consensus coordinates, see `Annotation`.
+Features that lie on a sequence not found in `G` are skipped.
+Return the number of features attached.
+"""
+function annotate!(G::Graph, features)
+    lengths = Dict(name => genomelength(path) for (name, path) in G.sequence)
+
+    attached = 0
+    for feature in features
+        feature.seqid ∈ keys(G.sequence) || continue
+
+        path   = G.sequence[feature.seqid]
+        L      = lengths[feature.seqid]
+        region = intervals(L, feature.start, feature.stop; circular=path.circular)
+        isempty(region) && continue
+        len    = sum(length(I) for I in region)
+
+        # (distance from the feature start, block, consensus interval, strand relative to consensus)
+        pieces = []
+        for (i, node) in enumerate(path.node)
+            covered = overlap(path, i, region, L)
+            covered === nothing && continue
+
+            push!(pieces, (
+                mod(path.position[i] + first(covered) - 1 - feature.start, L),
+                node.block,
+                consensus(node.block, node, covered),
+                node.strand ? feature.strand : flip(feature.strand),
+            ))
+        end
+        isempty(pieces) && continue
+        sort!(pieces; by=first)
+
+        for (k, (_, b, interval, strand)) in enumerate(pieces)
+            push!(get!(G.annotation, b.uuid, Annotation[]), Annotation(
+                feature.id, feature.seqid, feature.source, feature.type,
+                first(interval), last(interval), strand,
+                feature.phase, feature.attributes,
+                k, length(pieces), len,
+            ))
+        end
+        attached += 1
+    end
+
+    return attached
+end
+
+# ------------------------------------------------------------------------
+# projection onto isolates
+
+# pieces of one occurrence of a feature along a path
+mutable struct Occurrence
+    first  :: Int   # index of the first node carrying the feature
+    last   :: Int   # index of the last node carrying the feature
+    parts  :: Set{Int}
+    pieces :: Array{Any,1}
+end
+
+# alignment column of each consensus nucleotide of block `b`
+function reference(b::Block)
+    _, _, ref = alignment(b)
+    return [c for c in 1:length(ref) if ref[c] != UInt8('-')]
+end
+
+"""
+    project(G::Graph; exclude=[])
+
+Transfer the features attached to the blocks of graph `G` onto every isolate, excluding the isolates listed in `exclude`.
+Features are mapped from the consensus through the alignment of each copy of their block; nucleotides deleted in a copy are dropped.
+Parts of a feature found in consecutive nodes form one occurrence of the feature; a new occurrence starts whenever a part repeats or a node is skipped.
+Copies of a feature found in duplicated regions are identified by `ID#copy`, numbered by occurrence along the isolate.
+
+Return a dictionary mapping isolate names to arrays of GFF3 records, as named tuples, ordered by position.
+Coordinates are 1-based and refer to the original rotation of circular genomes; records crossing the origin end beyond the isolate length.
+Attributes record the isolate the feature was annotated on as `pangraph_source`, and flag features
+split across several blocks (`pangraph_split`), whose projected length differs from the original (`pangraph_disrupted`)
+or of which some pieces are missing (`pangraph_partial`).
+"""
+function project(G::Graph; exclude=[])
+    cache = Dict{Block,Any}()
+    aligned(b) = get!(cache, b) do
+        (reference(b), columns(b))
+    end
+
+    records = Dict{String,Any}()
+    for (name, path) in G.sequence
+        name ∈ exclude && continue
+
+        L = genomelength(path)
+        n = length(path.node)
+        # (feature id, source isolate) -> occurrences along the path
+        found = Dict{Tuple{String,String},Array{Occurrence,1}}()
+        for (j, node) in enumerate(path.node)
+            node.block.uuid ∈ keys(G.annotation) || continue
+
+            ref, cols = aligned(node.block)
+            cols = cols[node]
+            len  = length(cols)
+            for a in G.annotation[node.block.uuid]
+                occurrences = get!(found, (a.id, a.isolate), Occurrence[])
+                if isempty(occurrences) || occurrences[end].last < j-1 || a.part ∈ occurrences[end].parts
+                    push!(occurrences, Occurrence(j, j, Set{Int}(), []))
+                end
+                occurrence = occurrences[end]
+                occurrence.last = j
+                push!(occurrence.parts, a.part)
+
+                r₁ = searchsortedfirst(cols, ref[a.start])
+                r₂ = searchsortedlast(cols, ref[a.stop])
+                r₁ ≤ r₂ || continue
+
+                x₁, x₂ = node.strand ? (r₁, r₂) : (len-r₂+1, len-r₁+1)
+                push!(occurrence.pieces, (
+                    annotation = a,
+                    start      = mod1(path.position[j] + x₁ - 1, L),
+                    span       = x₂ - x₁ + 1,
+                    strand     = node.strand ? a.strand : flip(a.strand),
+                ))
+            end
+        end
+
+        # NOTE: occurrences of circular genomes must not be split at the origin
+        if path.circular
+            for occurrences in values(found)
+                length(occurrences) > 1 || continue
+                head, tail = occurrences[1], occurrences[end]
+                if head.first == 1 && tail.last == n && isempty(intersect(head.parts, tail.parts))
+                    union!(head.parts, tail.parts)
+                    prepend!(head.pieces, tail.pieces)
+                    pop!(occurrences)
+                end
+            end
+        end
+
+        records[name] = []
+        for ((id, _), occurrences) in found
+            filter!((o) -> !isempty(o.pieces), occurrences)
+            for (copy, occurrence) in enumerate(occurrences)
+                pieces = occurrence.pieces
+                a      = first(pieces).annotation
+                flags  = ["pangraph_source=$(a.isolate)"]
+                a.parts > 1 && push!(flags, "pangraph_split=true")
+                sum(p.span for p in pieces) != a.length && push!(flags, "pangraph_disrupted=true")
+                length(unique(p.annotation.part for p in pieces)) < a.parts && push!(flags, "pangraph_partial=true")
+
+                attributes = filter(!isempty, split(a.attributes, ';'))
+                attributes = [copy > 1 ? "ID=$(id)#$(copy)" : "ID=$(id)"; filter((x) -> !startswith(x, "ID="), attributes); flags]
+
+                for p in pieces
+                    push!(records[name], (
+                        seqid      = name,
+                        source     = p.annotation.source,
+                        type       = p.annotation.type,
+                        start      = p.start,
+                        stop       = p.start + p.span - 1,
+                        strand     = p.strand,
+                        phase      = p.annotation.phase,
+                        attributes = join(attributes, ';'),
+                    ))
+                end
+            end
+        end
+        sort!(records[name]; by=(r) -> (r.start, r.stop))
+    end
+
+    return records
+end
+
+"""
+    write_gff(io::IO, isolate, L, records; circular=false)
+
+Write the GFF3 `records` of `isolate`, of length `L`, as returned by `project`, to IO stream `io`.
+Circular isolates are declared by a `region` feature with attribute `Is_circular=true`, as required for records crossing the origin.
+"""
+function write_gff(io::IO, isolate, L, records; circular=false)
+    println(io, "##gff-version 3")
+    println(io, "##sequence-region $(isolate) 1 $(L)")
+    circular && println(io, join([isolate, "pangraph", "region", 1, L, '.', '.', "ID=$(isolate);Is_circular=true"], '\t'))
+    for r in records
+        println(io, join([r.seqid, r.source, r.type, r.start, r.stop, r.strand, r.phase, r.attributes], '\t'))
+    end
+end
+
+end
@@ -0,0 +1,53 @@
+module BED
+
+import ..Graphs: Graph, Block, Node, depth, genomelength, marshal_bed
+
+"""
+    records(G::Graph, isolate)
+
+Return the BED6 records of the nodes of genome `isolate` of graph `G`, sorted by position.
+Each node yields one interval named after its block, scored by the depth of its block and stranded by its orientation.
+Coordinates are 0-based, half-open and refer to the original rotation of circular genomes.
+Nodes crossing the origin of a circular genome are split into two records.
+"""
+function records(G::Graph, isolate)
+    isolate ∈ keys(G.sequence) || error("'$(isolate)' not a valid sequence identifier")
+
+    path = G.sequence[isolate]
+    L    = genomelength(path)
+
+    result = []
+    for (i, node) in enumerate(path.node)
+        len = length(node)
+        len > 0 || continue
+
+        start = path.position[i]
+        stop  = start + len - 1
+        # NOTE: bed scores are capped at 1000 by specification
+        entry = (name=node.block.uuid, score=min(depth(node.block), 1000), strand=node.strand ? '+' : '-')
+        if stop ≤ L
+            push!(result, (chrom=isolate, start=start-1, stop=stop, entry...))
+        else
+            push!(result, (chrom=isolate, start=start-1, stop=L, entry...))
+            push!(result, (chrom=isolate, start=0, stop=stop-L, entry...))
+        end
+    end
+
+    return sort!(result; by=(r) -> (r.start, r.stop))
+end
+
+"""
+    marshal_bed(io::IO, G::Graph; opt=nothing)
+
+Serialize the block coordinates of one genome of graph `G` as a BED6 file to IO stream `io`.
+`opt` must be a named tuple with field `isolate` naming the genome, see `records`.
+"""
+function marshal_bed(io::IO, G::Graph; opt=nothing)
+    (opt === nothing || !haskey(opt, :isolate)) && error("bed export requires an isolate")
+
+    for r in records(G, opt.isolate)
+        println(io, join([r.chrom, r.start, r.stop, r.name, r.score, r.strand], '\t'))
+    end
+end
+
+end
@@ -0,0 +1,243 @@
+module Binary
+
+import JSON
+import ..Graphs:
+    Graph, Block, Node, Path, Annotation,
+    SNPMap, InsMap, DelMap,
+    marshal_binary, unmarshal_binary
+
+export MAGIC
+
+# NOTE: the leading byte is not valid ASCII and thus distinguishes binary input from json/gfa input
+const MAGIC   = UInt8[0x89, UInt8('P'), UInt8('G'), UInt8('R')]
+const FORMAT  = 3
+
+# record tags
+const META  = UInt8('M')
+const PATH  = UInt8('P')
+const BLOCK = UInt8('B')
+const ANNOT = UInt8('A')
+const END   = UInt8('E')
+
+# NOTE: each format version only adds record types, the layout of existing records is unchanged
+#       older streams are thus read as is, with the records they lack left empty
+const RECORDS = Dict(
+    1 => (PATH, BLOCK),
+    2 => (META, PATH, BLOCK),
+    3 => (META, PATH, BLOCK, ANNOT),
+)
+
+# ------------------------------------------------------------------------
+# primitives (little endian)
+
+put(io::IO, x::Integer) = write(io, htol(Int64(x)))
+put(io::IO, x::Bool)    = write(io, UInt8(x))
+put(io::IO, x::UInt8)   = write(io, x)
+put(io::IO, x::Array{UInt8}) = (put(io, length(x)); write(io, x))
+put(io::IO, x::String)  = put(io, Array{UInt8}(x))
+
+getint(io::IO)   = Int(ltoh(read(io, Int64)))
+getbool(io::IO)  = read(io, UInt8) != 0x00
+getbytes(io::IO) = let n = getint(io)
+    bytes = read(io, n)
+    length(bytes) == n || error("truncated binary pangraph")
+    bytes
+end
+getstring(io::IO) = String(getbytes(io))
+
+# ------------------------------------------------------------------------
+# records
+
+# path record:
+#   tag, name, circular, has offset, offset, positions, nodes as (block uuid, strand)
+function put(io::IO, p::Path)
+    put(io, PATH)
+    put(io, p.name)
+    put(io, p.circular)
+    put(io, p.offset !== nothing)
+    put(io, p.offset === nothing ? 0 : p.offset)
+
+    put(io, length(p.position))
+    for x in p.position
+        put(io, x)
+    end
+
+    put(io, length(p.node))
+    for node in p.node
+        put(io, node.block.uuid)
+        put(io, node.strand)
+    end
+end
+
+# block record:
+#   tag, uuid, consensus, gaps, alleles of each node keyed by (path index, node index)
+function put(io::IO, b::Block, index)
+    put(io, BLOCK)
+    put(io, b.uuid)
+    put(io, b.sequence)
+
+    put(io, length(b.gaps))
+    for (x, len) in b.gaps
+        put(io, x)
+        put(io, len)
+    end
+
+    put(io, length(b.mutate))
+    for node in keys(b.mutate)
+        i, j = index[node]
+        put(io, i)
+        put(io, j)
+
+        put(io, length(b.mutate[node]))
+        for (x, nuc) in b.mutate[node]
+            put(io, x)
+            put(io, nuc)
+        end
+
+        put(io, length(b.insert[node]))
+        for ((x, δ), seq) in b.insert[node]
+            put(io, x)
+            put(io, δ)
+            put(io, seq)
+        end
+
+        put(io, length(b.delete[node]))
+        for (x, len) in b.delete[node]
+            put(io, x)
+            put(io, len)
+        end
+    end
+end
+
+"""
+    marshal_binary(io::IO, G::Graph; opt=nothing)
+
+Serialize graph `G` to IO stream `io` using the versioned binary format of PanGraph.
+The stream starts with a magic number and the format version, followed by the graph metadata, one record per path, one record per block, one record per annotated block and a terminating tag.
+Records are written one at a time; no intermediate representation of the full graph is built.
+
+`opt` is currently ignored. It is kept for signature uniformity for other marshal functions
+"""
+function marshal_binary(io::IO, G::Graph; opt=nothing)
+    write(io, MAGIC)
+    put(io, FORMAT)
+
+    # metadata record: tag, json encoded metadata
+    put(io, META)
+    put(io, JSON.json(G.metadata))
+
+    # NOTE: paths must come first as they fill the node lookup table
+    index = Dict{Node{Block},Tuple{Int,Int}}()
+    for (i, path) in enumerate(values(G.sequence))
+        for (j, node) in enumerate(path.node)
+            index[node] = (i, j)
+        end
+        put(io, path)
+    end
+
+    for block in values(G.block)
+        put(io, block, index)
+    end
+
+    # annotation record: tag, block uuid, json encoded features
+    for (uuid, features) in G.annotation
+        uuid ∈ keys(G.block) || continue
+        put(io, ANNOT)
+        put(io, uuid)
+        put(io, JSON.json(features))
+    end
+
+    put(io, END)
+end
+
+"""
+    unmarshal_binary(io::IO)
+
+Deserialize the binary formatted input stream `io` into a Graph data structure.
+Records are read one at a time.
+Streams of older format versions are accepted, provided they only contain the records known to their version.
+Return a `Graph` type.
+"""
+function unmarshal_binary(io::IO)
+    read(io, length(MAGIC)) == MAGIC || error("input is not a binary pangraph")
+    version = getint(io)
+    version ≤ FORMAT || error("binary pangraph version $(version) is newer than supported version $(FORMAT)")
+    version ∈ keys(RECORDS) || error("unrecognized binary pangraph version $(version)")
+
+    # NOTE: blocks are allocated once first referenced by a path and filled by their own record
+    stub   = (uuid) -> Block(
+        uuid,
+        UInt8[],
+        Dict{Int,Int}(),
+        Dict{Node{Block},SNPMap}(),
+        Dict{Node{Block},InsMap}(),
+        Dict{Node{Block},DelMap}(),
+    )
+    blocks = Dict{String,Block}()
+    filled = Set{String}()
+    paths  = Dict{String,Path}()
+    nodes  = Array{Node{Block},1}[]
+    meta   = Dict{String,Any}()
+    annot  = Dict{String,Array{Annotation,1}}()
+
+    while true
+        tag = read(io, UInt8)
+        if tag == END
+            break
+        end
+        tag ∈ RECORDS[version] || error("unrecognized record '$(Char(tag))' in binary pangraph version $(version)")
+
+        if tag == META
+            meta = Dict{String,Any}(JSON.parse(getstring(io)))
+        elseif tag == PATH
+            name     = getstring(io)
+            circular = getbool(io)
+            hasoff   = getbool(io)
+            offset   = getint(io)
+
+            position = [getint(io) for _ in 1:getint(io)]
+            node     = map(1:getint(io)) do _
+                uuid = getstring(io)
+                Node{Block}(get!(() -> stub(uuid), blocks, uuid), getbool(io))
+            end
+
+            push!(nodes, node)
+            paths[name] = Path(name, node, hasoff ? offset : nothing, circular, position)
+        elseif tag == BLOCK
+            uuid  = getstring(io)
+            block = get!(() -> stub(uuid), blocks, uuid)
+
+            block.sequence = getbytes(io)
+            for _ in 1:getint(io)
+                x = getint(io)
+                block.gaps[x] = getint(io)
+            end
+
+            for _ in 1:getint(io)
+                i, j = getint(io), getint(io)
+                node = nodes[i][j]
+
+                block.mutate[node] = SNPMap(getint(io) => read(io, UInt8) for _ in 1:getint(io))
+                block.insert[node] = InsMap((getint(io), getint(io)) => getbytes(io) for _ in 1:getint(io))
+                block.delete[node] = DelMap(getint(io) => getint(io) for _ in 1:getint(io))
+            end
+
+            push!(filled, uuid)
+        elseif tag == ANNOT
+            uuid = getstring(io)
+            annot[uuid] = [Annotation(a) for a in JSON.parse(getstring(io))]
+        end
+    end
+
+    for uuid in keys(blocks)
+        uuid ∈ filled || error("block '$(uuid)' referenced by a path but not stored")
+    end
+
+    for uuid in keys(annot)
+        uuid ∈ filled || error("annotations stored for unknown block '$(uuid)'")
+    end
+
+    return Graph(blocks, paths, meta, annot)
+end
+
+end
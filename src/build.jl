@@ -1,3 +1,75 @@
+# ------------------------------------------------------------------------
+# alignment parameters shared by all subcommands that merge graphs
+
+"""
+	alignment_energy(minblock, α, β)
+
+Return the energy function used to score a pairwise alignment between pancontigs.
+Alignments shorter than `minblock` are rejected.
+`α` is the cost of each junction introduced by the merger, `β` the cost of each mutation.
+"""
+function alignment_energy(minblock, α, β)
+    return function(aln)
+        len = aln.length
+        len < minblock && return Inf
+
+        cuts(hit) = (hit.start > minblock) + ((hit.length-hit.stop) > minblock)
+
+        ncuts = cuts(aln.qry)+cuts(aln.ref)
+        nmuts = aln.divergence*aln.length
+
+        return -len + α*ncuts + β*nmuts
+    end
+end
+
+"""
+	alignment_sensitivity(s)
+
+Map the command line sensitivity `s` onto a minimap2 preset.
+Return `nothing` if `s` is not recognized.
+"""
+alignment_sensitivity(s) = @match s begin
+    "5"  => "asm5"
+    "10" => "asm10"
+    "20" => "asm20"
+     _   => nothing
+end
+
+"""
+	distance_backend(name)
+
+Return the function used to estimate pairwise distances for the guide tree.
+Return `nothing` if `name` is not recognized.
+"""
+distance_backend(name) = @match name begin
+    "native" => Graphs.Mash.distance
+    "mash"   => begin
+        if !Graphs.havecommand("mash")
+            panic("external command mash not found. either install or use native backend\n")
+        end
+        Graphs.mash
+    end
+    _        => nothing
+end
+
+"""
+	alignment_kernel(kernel, minblock, sensitivity, kmer)
+
+Return the function used to compute pairwise alignments between two sets of pancontigs.
+"""
+function alignment_kernel(kernel, minblock, sensitivity, kmer)
+    return (contigs₁, contigs₂) -> @match kernel begin
+        "minimap2" => Minimap.align(contigs₁, contigs₂, minblock, sensitivity)
+        "mmseqs"   => let
+            if !Shell.havecommand("mmseqs")
+                panic("external command mmseqs not found. please install before running build step with mmseqs backend\n")
+            end
+            MMseqs.align(contigs₁, contigs₂, kmer)
+        end
+                 _ => error("unrecognized alignment kernel")
+    end
+end
+
 Build = Command(
    "build",
    "pangraph build <options> [arguments]",
@@ -76,7 +148,28 @@ Build = Command(
         (short="-K", long="--kmer-length"),
         "kmer length, only used for mmseqs2 alignment kernel. If not specified will use mmseqs default.",
         0,
-    )
+    ),
+    Arg(
+        String,
+        "output format",
+        (short="-f", long="--format"),
+        "format of the output pangraph\n\trecognized options: [json, binary]",
+        "json",
+    ),
+    Arg(
+        String,
+        "guide tree",
+        (short="-t", long="--guide-tree"),
+        "path to a newick tree used as guide tree instead of estimating pairwise distances\n\tleaves must be named by the fasta records",
+        "",
+    ),
+    Arg(
+        String,
+        "guide tree output",
+        (short="-T", long="--guide-tree-out"),
+        "path to store the guide tree in newick format\n\tif empty, the guide tree is not stored",
+        "",
+    ),
    ],
 
    (args) -> let
@@ -90,63 +183,55 @@ Build = Command(
        minblock  = arg(Build, "-l")
        circular  = arg(Build, "-c")
        uppercase = arg(Build, "-u")
+       fmt       = outputformat(Build)
 
-       α = arg(Build, "-a")
-       β = arg(Build, "-b")
-
-       energy = function(aln)
-           len = aln.length
-           len < minblock && return Inf
-
-           cuts(hit) = (hit.start > minblock) + ((hit.length-hit.stop) > minblock)
-
-           ncuts = cuts(aln.qry)+cuts(aln.ref)
-           nmuts = aln.divergence*aln.length
-
-           return -len + α*ncuts + β*nmuts
-       end
-
+       energy  = alignment_energy(minblock, arg(Build, "-a"), arg(Build, "-b"))
        maxiter = arg(Build, "-x")
-       sensitivity = @match arg(Build, "-s") begin
-           "5"  => "asm5"
-           "10" => "asm10"
-           "20" => "asm20"
-            _   => begin
-                usage(Build)
-                exit(1)
-            end
+
+       sensitivity = alignment_sensitivity(arg(Build, "-s"))
+       if sensitivity === nothing
+           usage(Build)
+           exit(1)
        end
 
        graph(io) = graphs(io; circular=circular, upper=uppercase)
        isolates  = (G for file in files for G ∈ open(graph,file))
 
-       compare = @match arg(Build, "-d") begin
-           "native" => Graphs.Mash.distance
-           "mash"   => begin
-               if !Graphs.havecommand("mash")
-                   panic("external command mash not found. either install or use native backend\n")
-               end
-               Graphs.mash
-           end
-           _        => begin
-                # XXX: hacky...
-                Build.arg[8].value = "native"
+       compare = distance_backend(arg(Build, "-d"))
+       if compare === nothing
+           # XXX: hacky...
+           Build.arg[8].value = "native"
 
-                usage(Build)
-                exit(1)
+           usage(Build)
+           exit(1)
+       end
+
+       aligner = alignment_kernel(arg(Build, "-k"), minblock, sensitivity, arg(Build, "-K"))
+
+       guide  = arg(Build, "-t")
+       output = arg(Build, "-T")
+       tree   = if length(guide) > 0
+           isfile(guide) || panic("guide tree '$(guide)' not found\n")
+           isolates = collect(isolates)
+           try
+               user = Graphs.parse_newick(read(guide, String))
+               Graphs.checkguide(user, [collect(keys(G.sequence))[1] for G in isolates])
+               user
+           catch err
+               err isa ErrorException || rethrow()
+               panic("invalid guide tree '$(guide)': $(err.msg)\n")
            end
+       elseif length(output) > 0
+           isolates = collect(isolates)
+           Graphs.guidetree(compare, isolates...)
+       else
+           nothing
        end
 
-       aligner(contigs₁, contigs₂) = @match arg(Build, "-k") begin
-           "minimap2" => Minimap.align(contigs₁, contigs₂, minblock, sensitivity)
-           "mmseqs"   => let
-               if !Shell.havecommand("mmseqs")
-                   panic("external command mmseqs not found. please install before running build step with mmseqs backend\n")
-               end
-               kmer_len = arg(Build, "-K")
-               MMseqs.align(contigs₁, contigs₂, kmer_len)
+       if length(output) > 0
+           Base.open(output, "w") do io
+               println(io, Graphs.newick(tree))
            end
-                    _ => error("unrecognized alignment kernel")
        end
 
        graph = Graphs.align(aligner, isolates...;
@@ -154,9 +239,11 @@ Build = Command(
             energy      = energy,
             minblock    = minblock,
             maxiter     = maxiter,
+            guide       = tree,
        )
        finalize!(graph)
+       provenance!(graph, Build, files)
 
-       marshal(stdout, graph; fmt=:json)
+       marshal(stdout, graph; fmt=fmt)
    end
 )
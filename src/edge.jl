@@ -340,6 +340,9 @@ function deparalog!(G)
             end
 
             G.block[new.uuid] = new
+            if block.uuid ∈ keys(G.annotation)
+                G.annotation[new.uuid] = copy(G.annotation[block.uuid])
+            end
         end
     end
 end
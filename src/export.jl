@@ -4,7 +4,7 @@ Export = Command(
    "export",
    "pangraph export <options> [arguments]",
    "exports a pangraph to a chosen file format(s)",
-   """zero or one pangraph file (native json)
+   """zero or one pangraph file (json, binary or gfa)
       if no file given, reads from stdin
       stream can be optionally gzipped.""",
    [
@@ -85,6 +85,104 @@ Export = Command(
         "do not emit GFA file",
         false,
     ),
+    Arg(
+        String,
+        "GFA version",
+        (short="-gv", long="--gfa-version"),
+        "specification of the emitted GFA file\n\trecognized options: [1.0, 1.1, 2.0]",
+        "1.1",
+    ),
+    Arg(
+        Bool,
+        "export VCF",
+        (short="-vcf", long="--export-vcf"),
+        "emit VCF file of polymorphisms relative to the consensus of each block",
+        false,
+    ),
+    Arg(
+        String,
+        "VCF reference isolate",
+        (short="-vr", long="--vcf-reference"),
+        "emit VCF file of polymorphisms projected onto the coordinates of the given isolate\n\tif empty, will skip this computation",
+        "",
+    ),
+    Arg(
+        Bool,
+        "export BED",
+        (short="-bed", long="--export-bed"),
+        "emit one BED file per isolate with the coordinates of every block along its genome",
+        false,
+    ),
+    Arg(
+        Bool,
+        "export core alignment",
+        (short="-ca", long="--core-alignment"),
+        "emit concatenated multiple sequence alignment of single-copy core blocks",
+        false,
+    ),
+    Arg(
+        Float64,
+        "core threshold",
+        (short="-ct", long="--core-threshold"),
+        "minimum fraction of isolates a single-copy block must be found in to be considered core\n\tmissing isolates are padded with gaps",
+        1.0,
+    ),
+    Arg(
+        Bool,
+        "core SNPs only",
+        (short="-cs", long="--core-snps"),
+        "only emit polymorphic sites of the core alignment",
+        false,
+    ),
+    Arg(
+        String,
+        "core alignment format",
+        (short="-cf", long="--core-format"),
+        "file format of the core alignment\n\trecognized options: [fasta, phylip]",
+        "fasta",
+    ),
+    Arg(
+        Bool,
+        "export presence/absence matrix",
+        (short="-pa", long="--presence-absence"),
+        "emit block presence/absence (copy number) matrix and Roary-compatible gene_presence_absence.csv",
+        false,
+    ),
+    Arg(
+        String,
+        "presence/absence matrix format",
+        (short="-paf", long="--presence-absence-format"),
+        "delimiter of the block presence/absence matrix\n\trecognized options: [tsv, csv]",
+        "tsv",
+    ),
+    Arg(
+        String,
+        "block alignments directory",
+        (short="-ba", long="--block-alignments"),
+        "path to directory where the multiple sequence alignment of every block will be stored\n\tif empty, will skip this computation",
+        "",
+    ),
+    Arg(
+        String,
+        "block alignment format",
+        (short="-baf", long="--block-alignment-format"),
+        "file format of the block alignments\n\trecognized options: [fasta, stockholm]",
+        "fasta",
+    ),
+    Arg(
+        Bool,
+        "export MAF",
+        (short="-maf", long="--export-maf"),
+        "emit multiple sequence alignment of all blocks as a single MAF file",
+        false,
+    ),
+    Arg(
+        Bool,
+        "export HTML visualization",
+        (short="-html", long="--export-html"),
+        "emit self-contained HTML page drawing every genome as a track of blocks",
+        false,
+    ),
     Arg(
         Bool,
         "export panX visualization",
@@ -151,14 +249,122 @@ Export = Command(
 
        # GFA export (default)
        if !arg(Export, "-ng")
+           version = @match arg(Export, "-gv") begin
+               "1.0"        => "1.0"
+               "1.1"        => "1.1"
+               "2" || "2.0" => "2.0"
+                _           => begin
+                    usage(Export)
+                    exit(1)
+                end
+           end
+
            Base.open("$(directory)/$(prefix).gfa", "w") do io
-               marshal(io, graph; fmt=:gfa, opt=filter)
+               marshal(io, graph; fmt=:gfa, opt=merge(filter, (version=version,)))
            end
            Base.open("$(directory)/$(prefix).fa", "w") do io
                marshal(io, graph; fmt=:fa)
            end
        end
 
+       # VCF export
+       if arg(Export, "-vcf")
+           Base.open("$(directory)/$(prefix).vcf", "w") do io
+               marshal(io, graph; fmt=:vcf)
+           end
+       end
+
+       reference = arg(Export, "-vr")
+       if length(reference) > 0
+           if reference ∉ keys(graph.sequence)
+               panic("isolate '$(reference)' not found in pangraph\n")
+           end
+           Base.open("$(directory)/$(prefix)_$(filename(reference)).vcf", "w") do io
+               marshal(io, graph; fmt=:vcf, opt=(reference=reference,))
+           end
+       end
+
+       # block coordinates along each genome
+       if arg(Export, "-bed")
+           for isolate in keys(graph.sequence)
+               Base.open("$(directory)/$(prefix)_$(filename(isolate)).bed", "w") do io
+                   marshal(io, graph; fmt=:bed, opt=(isolate=isolate,))
+               end
+           end
+       end
+
+       # core genome alignment
+       if arg(Export, "-ca")
+           format, suffix = @match arg(Export, "-cf") begin
+               "fasta"  => (:fasta, "fa")
+               "phylip" => (:phylip, "phy")
+                _       => begin
+                    usage(Export)
+                    exit(1)
+                end
+           end
+
+           names, aln = Graphs.Alignments.core(graph;
+                threshold = arg(Export, "-ct"),
+                snps      = arg(Export, "-cs"),
+           )
+           Base.open("$(directory)/$(prefix)_core.$(suffix)", "w") do io
+               Graphs.Alignments.write_alignment(io, names, aln; fmt=format)
+           end
+       end
+
+       # block presence/absence matrix
+       if arg(Export, "-pa")
+           format = @match arg(Export, "-paf") begin
+               "tsv" => :tsv
+               "csv" => :csv
+                _    => begin
+                    usage(Export)
+                    exit(1)
+                end
+           end
+
+           Base.open("$(directory)/$(prefix)_blocks.$(format)", "w") do io
+               Graphs.PresenceAbsence.write_matrix(io, graph; fmt=format)
+           end
+           Base.open("$(directory)/$(prefix)_gene_presence_absence.csv", "w") do io
+               Graphs.PresenceAbsence.write_matrix(io, graph; fmt=:roary)
+           end
+       end
+
+       # per-block multiple sequence alignments
+       blocks = arg(Export, "-ba")
+       if length(blocks) > 0
+           format, suffix = @match arg(Export, "-baf") begin
+               "fasta"     => (:fasta, "fa")
+               "stockholm" => (:stockholm, "sto")
+                _          => begin
+                    usage(Export)
+                    exit(1)
+                end
+           end
+
+           isdir(blocks) || mkpath(blocks)
+           locus = Graphs.Alignments.loci(graph)
+           for b in values(graph.block)
+               Base.open("$(blocks)/$(b.uuid).$(suffix)", "w") do io
+                   Graphs.Alignments.write_block(io, b, locus; fmt=format)
+               end
+           end
+       end
+
+       if arg(Export, "-maf")
+           Base.open("$(directory)/$(prefix).maf", "w") do io
+               Graphs.Alignments.write_maf(io, graph)
+           end
+       end
+
+       if arg(Export, "-html")
+           Base.open("$(directory)/$(prefix).html", "w") do io
+               marshal(io, graph; fmt=:html, opt=(title=prefix,))
+           end
+       end
+
        # panX export (doesn't fit into marshal paradigm)
        if arg(Export, "-pX")
            if !Shell.havecommand("fasttree")
@@ -0,0 +1,75 @@
+Extract = Command(
+   "extract",
+   "pangraph extract <options> [arguments]",
+   "extracts the sub-graph homologous to a region of one isolate",
+   """zero or one pangraph file (json, binary or gfa)
+      if no file, reads from stdin
+      stream can be optionally gzipped.""",
+   [
+    Arg(
+        String,
+        "isolate",
+        (short="-i", long="--isolate"),
+        "name of the isolate whose coordinates define the region",
+        "",
+    ),
+    Arg(
+        String,
+        "region",
+        (short="-r", long="--region"),
+        "interval of the isolate to extract, formatted as start-end\n\t1-based and inclusive, relative to the original rotation of the isolate",
+        "",
+    ),
+    Arg(
+        Int,
+        "flank length",
+        (short="-n", long="--flank"),
+        "number of nucleotides to include on both sides of the region",
+        0,
+    ),
+    Arg(
+        String,
+        "output format",
+        (short="-f", long="--format"),
+        "format of the output pangraph\n\trecognized options: [json, binary]",
+        "json",
+    ),
+   ],
+
+   function(args)
+       path = parse(Extract, args)
+       path = if (path === nothing || length(path) == 0)
+           nothing
+       elseif length(path) == 1
+           path
+       else
+           usage(Extract)
+           return 2
+       end
+
+       isolate = arg(Extract, "-i")
+       region  = arg(Extract, "-r")
+       if length(isolate) == 0 || length(region) == 0
+           usage(Extract)
+           return 2
+       end
+
+       interval = match(r"^(\d+)-(\d+)$", region)
+       interval === nothing && panic("region '$(region)' not formatted as start-end\n")
+       start, stop = parse(Int, interval[1]), parse(Int, interval[2])
+
+       flank = arg(Extract, "-n")
+       flank ≥ 0 || panic("flank length must be non-negative\n")
+
+       fmt   = outputformat(Extract)
+       graph = load(path, Extract)
+       isolate ∈ keys(graph.sequence) || panic("isolate '$(isolate)' not found in pangraph\n")
+
+       history = [copy(graph.metadata)]
+       graph   = Graphs.Regions.extract(graph, isolate, start, stop; flank=flank)
+       provenance!(graph, Extract, path === nothing ? [] : path; history=history)
+
+       marshal(stdout, graph; fmt=fmt)
+       return 0
+   end
+)
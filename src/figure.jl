@@ -0,0 +1,94 @@
+module Figures
+
+import ..Graphs: Graph, depth, genomelength
+import ..Viewer: color
+
+export write_svg
+
+escape(s) = replace(s, "&" => "&amp;", "<" => "&lt;", ">" => "&gt;", "\"" => "&quot;")
+
+# 1, 2 or 5 times a power of ten, close to a tenth of the longest genome
+function scalebar(L)
+    L > 0 || return 1
+    x = 10.0^floor(log10(L/10))
+    for m in (1, 2, 5, 10)
+        m*x ≥ L/10 && return round(Int, m*x)
+    end
+end
+
+# human readable genome length
+function units(x)
+    x ≥ 1_000_000 && return "$(round(x/1_000_000; digits=2)) Mb"
+    x ≥ 1_000     && return "$(round(x/1_000; digits=2)) kb"
+    return "$(x) bp"
+end
+
+"""
+    write_svg(io::IO, G::Graph; order=nothing, keep=(b)->true, width=1000, height=12)
+
+Draw every isolate of graph `G` as a row of arrows, one per block, to IO stream `io` as a scalable vector graphic.
+Arrows are colored by block identifier, consistently with the HTML export, point along the strand of the block and are placed in the original rotation of circular genomes.
+Blocks for which `keep` returns false are not drawn; their genome stretch is left as a line.
+
+Rows are ordered following the isolate names in `order`, if given, and alphabetically otherwise.
+`width` sets the length, in pixels, of the longest genome and `height` the thickness of the arrows.
+"""
+function write_svg(io::IO, G::Graph; order=nothing, keep=(b)->true, width=1000, height=12)
+    names = order === nothing ? sort(collect(keys(G.sequence))) : collect(order)
+    for name in names
+        name ∈ keys(G.sequence) || error("'$(name)' not a valid sequence identifier")
+    end
+
+    lengths = Dict(name => genomelength(G.sequence[name]) for name in names)
+    longest = maximum(values(lengths); init=1)
+
+    # NOTE: label widths are estimated assuming an average glyph width of 0.6 em
+    margin = 10 + ceil(Int, 0.6*12*maximum(length.(names); init=0))
+    row    = 2*height + 6
+    total  = (W = margin + width + 20, H = row*length(names) + 50)
+    x(pos) = margin + (pos - 1)/longest*width
+
+    println(io, """<?xml version="1.0" encoding="UTF-8"?>""")
+    println(io, """<svg xmlns="http://www.w3.org/2000/svg" width="$(total.W)" height="$(total.H)" viewBox="0 0 $(total.W) $(total.H)" font-family="sans-serif" font-size="12">""")
+
+    for (r, name) in enumerate(names)
+        path = G.sequence[name]
+        L    = lengths[name]
+        y    = 10 + (r-1)*row + height/2
+
+        println(io, """  <g id="$(escape(name))">""")
+        println(io, """    <text x="$(margin-8)" y="$(y)" text-anchor="end" dominant-baseline="middle">$(escape(name))</text>""")
+        println(io, """    <line x1="$(x(1))" x2="$(x(L+1))" y1="$(y)" y2="$(y)" stroke="#999999" stroke-width="1"/>""")
+
+        for (i, node) in enumerate(path.node)
+            len = length(node)
+            (len > 0 && keep(node.block)) || continue
+
+            start = path.position[i]
+            stop  = start + len - 1
+            # NOTE: blocks crossing the origin are split, only the piece holding the end of the block along its strand carries the arrow tip
+            pieces = stop ≤ L ? [(start, stop, true)] : [(start, L, !node.strand), (1, stop-L, node.strand)]
+            for (lo, hi, tip) in pieces
+                x₀, x₁ = x(lo), x(hi+1)
+                t  = tip ? min(height/2, (x₁-x₀)/2) : 0
+                y₀, y₁ = y - height/2, y + height/2
+                points = if node.strand
+                    [(x₀,y₀), (x₁-t,y₀), (x₁,y), (x₁-t,y₁), (x₀,y₁)]
+                else
+                    [(x₁,y₀), (x₀+t,y₀), (x₀,y), (x₀+t,y₁), (x₁,y₁)]
+                end
+                coords = join(("$(round(px; digits=2)),$(round(py; digits=2))" for (px, py) in points), ' ')
+                println(io, """    <polygon points="$(coords)" fill="$(color(node.block.uuid))" stroke="#333333" stroke-width="0.5"><title>$(node.block.uuid) length=$(length(node.block)) depth=$(depth(node.block))</title></polygon>""")
+            end
+        end
+        println(io, "  </g>")
+    end
+
+    bar = scalebar(longest)
+    y   = total.H - 25
+    println(io, """  <line x1="$(x(1))" x2="$(x(bar+1))" y1="$(y)" y2="$(y)" stroke="#000000" stroke-width="2"/>""")
+    println(io, """  <text x="$((x(1)+x(bar+1))/2)" y="$(y+15)" text-anchor="middle">$(units(bar))</text>""")
+    println(io, "</svg>")
+end
+
+end
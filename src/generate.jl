@@ -54,6 +54,13 @@ Generate = Command(
         "number of generations simulated under WF model",
         35,
     ),
+    Arg(
+        String,
+        "output format",
+        (short="-f", long="--format"),
+        "format of the output pangraph\n\trecognized options: [json, binary]",
+        "json",
+    ),
    ],
    function(args)
         if args === nothing || length(args) == 0 
@@ -92,6 +99,7 @@ Generate = Command(
 
         T    = arg(Generate, "-t")
         path = arg(Generate, "-o")
+        fmt  = outputformat(Generate)
 
         sequences, graph = Simulation.run(evolve!, T, ancestors; graph=path!="")
 
@@ -100,8 +108,9 @@ Generate = Command(
         end
 
         if length(path) > 0
+            provenance!(graph, Generate, [input])
             open(path,"w") do io
-                marshal(io, graph; fmt=:json)
+                marshal(io, graph; fmt=fmt)
             end
         end
     end
@@ -1,7 +1,10 @@
 module GFA
 
 import Base: print
-import ..Graphs: Graph, sequence, marshal_gfa
+import ..Graphs:
+    Graph, Block, Nodes, Paths,
+    SNPMap, InsMap, DelMap,
+    sequence, depth, genomelength, marshal_gfa, unmarshal_gfa
 
 """
     struct Segment
@@ -19,8 +22,16 @@ struct Segment
     depth    :: Int
 end
 
-function print(io::IO, s::Segment)
-    print(io, "S\t$(s.name)\t*\tLN:i:$(length(s.sequence))\tRC:i:$((s.depth*length(s.sequence)))")
+# NOTE: all GFA lines are emitted through a version-aware emit
+#       recognized versions are "1.0", "1.1" and "2.0"
+function emit(io::IO, s::Segment, version)
+    # NOTE: GFA 1.0 output omits the sequence, which is stored within the accompanying fasta
+    seq = version == "1.0" ? "*" : String(copy(s.sequence))
+    if version == "2.0"
+        write(io, "S\t$(s.name)\t$(length(s.sequence))\t$(seq)\tRC:i:$((s.depth*length(s.sequence)))\n")
+    else
+        write(io, "S\t$(s.name)\t$(seq)\tLN:i:$(length(s.sequence))\tRC:i:$((s.depth*length(s.sequence)))\n")
+    end
 end
 
 const Node = NamedTuple{
@@ -49,8 +60,35 @@ mutable struct Link
     depth :: Int
 end
 
-function print(io::IO, l::Link)
-    print(io, "L\t$(l.from)\t$(l.to)\t*\tRC:i:$(l.depth)")
+# dovetail coordinates of a zero-length overlap in GFA2
+tail(n::Node) = n.orientation ? "$(length(n.segment.sequence))\$" : "0"
+head(n::Node) = n.orientation ? "0" : "$(length(n.segment.sequence))\$"
+
+function emit(io::IO, l::Link, version)
+    if version == "2.0"
+        from, to = l.from, l.to
+        write(io, "E\t*\t$(from.segment.name)$(polarity(from.orientation))\t$(to.segment.name)$(polarity(to.orientation))\t$(tail(from))\t$(tail(from))\t$(head(to))\t$(head(to))\t*\tRC:i:$(l.depth)\n")
+    else
+        write(io, "L\t$(l.from)\t$(l.to)\t0M\tRC:i:$(l.depth)\n")
+    end
+end
+
+"""
+    struct Walk
+        segments :: Array{Node,1}
+        start    :: Int
+        stop     :: Int
+        shift    :: Int
+    end
+
+Store a run of segments that are contiguous along a genome and span its nucleotides `start` to `stop`, 0-based and end-exclusive.
+`shift` is the number of nucleotides of the first segment that precede `start`, i.e. that precede the origin of a circular genome.
+"""
+struct Walk
+    segments :: Array{Node,1}
+    start    :: Int
+    stop     :: Int
+    shift    :: Int
 end
 
 """
@@ -58,24 +96,49 @@ end
         name     :: String
         segments :: Array{Node,1}
         circular :: Bool
+        walks    :: Array{Walk,1}
     end
 
 Store a GFA path, i.e. a sequence of segments that represents an observed genome.
+`walks` splits the segments into runs that are contiguous along the genome, as filtered segments leave gaps.
 """
 struct Path
     name     :: String
     segments :: Array{Node,1}
     circular :: Bool
+    walks    :: Array{Walk,1}
 end
 
-function print(io::IO, nodes::Array{Node,1})
-    print(io, join(("$(n.segment.name)$(polarity(n.orientation))" for n in nodes ), ","))
+circular(flag::Bool) =  flag ? "TP:Z:circular" : "TP:Z:linear"
+
+function emit(io::IO, p::Path, version)
+    if version == "2.0"
+        segments = join(("$(n.segment.name)$(polarity(n.orientation))" for n in p.segments), ' ')
+        write(io, "O\t$(p.name)\t$(segments)\t$(circular(p.circular))\n")
+    elseif version == "1.1"
+        for w in p.walks
+            walk  = join("$(n.orientation ? '>' : '<')$(n.segment.name)" for n in w.segments)
+            shift = w.shift > 0 ? "\tOF:i:$(w.shift)" : ""
+            write(io, "W\t$(p.name)\t0\t$(p.name)\t$(w.start)\t$(w.stop)\t$(walk)\t$(circular(p.circular))$(shift)\n")
+        end
+    else
+        segments = join(("$(n.segment.name)$(polarity(n.orientation))" for n in p.segments), ',')
+        overlaps = length(p.segments) > 1 ? join(fill("0M", length(p.segments)-1), ',') : "*"
+        write(io, "P\t$(p.name)\t$(segments)\t$(overlaps)\t$(circular(p.circular))\n")
+    end
 end
 
-circular(flag::Bool) =  flag ? "TP:Z:circular" : "TP:Z:linear"
+# index of the node of path `p` that contains the origin of its genome of length `L`,
+# along with the number of nucleotides of the node that precede the origin
+function origin(p, L)
+    n = length(p.node)
+    (p.circular && n > 0 && length(p.position) ≥ n) || return 1, 0
+
+    j = argmin(p.position[1:n])
+    p.position[j] == 1 && return j, 0
 
-function print(io::IO, p::Path)
-    print(io, "P\t$(p.name)\t$(p.segments)\t$(circular(p.circular))*")
+    i = mod1(j-1, n)
+    return i, L - p.position[i] + 1
 end
 
 """
@@ -85,6 +148,13 @@ Output pangraph `G` to IO stream `io`.
 `opt` can include two functions, to be accessed in fields `connect` and `output`.
 `connect` is a function that takes a node and returns true or false if it should be connected in the GFA output.
 `output` is an equivalent function signature, but controls whether the node is output at all.
+`opt` can further set the GFA specification in field `version`, either "1.0", "1.1" (default) or "2.0".
+
+GFA 1.0 output stores each genome as a P-line, GFA 1.1 output as W-lines tagged with its topology.
+Each W-line spans a run of nodes that are contiguous along the genome, such that filtered nodes split a genome into several W-lines.
+Walks of circular genomes start at the block that contains the origin; if the origin falls within the block,
+the number of nucleotides of the block that precede it is stored in the `OF:i` tag of the first W-line.
+GFA 2.0 output stores links as dovetail E-lines of zero overlap and genomes as O-groups.
 """
 function marshal_gfa(io::IO, G::Graph; opt=nothing)
     # unpack options
@@ -100,6 +170,9 @@ function marshal_gfa(io::IO, G::Graph; opt=nothing)
           )
     )
 
+    version = (opt !== nothing && hasproperty(opt, :version)) ? opt.version : "1.1"
+    version ∈ ("1.0", "1.1", "2.0") || error("$(version) not a recognized GFA version")
+
     # wrangle data
     segments = Dict(
         let
@@ -137,21 +210,31 @@ function marshal_gfa(io::IO, G::Graph; opt=nothing)
     end
 
     for (i,path) in enumerate(values(G.sequence))
-        nodes = [
-            let
-                segment = segments[node.block]
-                if segment !== nothing && !filter.connect(node)
-                    (
-                     segment     = segment,
-                     orientation = node.strand,
-                    )
-                else
-                    nothing
-                end
+        L        = genomelength(path)
+        j, shift = origin(path, L)
+
+        nodes = Node[]
+        walks = Walk[]
+        run   = Node[]
+
+        # x is the 0-based genome coordinate of the current node, negative before the origin
+        x, start = -shift, -shift
+        for node in circshift(path.node, 1-j)
+            segment = segments[node.block]
+            if segment !== nothing && !filter.connect(node)
+                isempty(run) && (start = x)
+                push!(run, (segment=segment, orientation=node.strand))
+            elseif !isempty(run)
+                push!(walks, Walk(run, max(start, 0), x, max(-start, 0)))
+                append!(nodes, run)
+                run = Node[]
             end
-            for node in path.node
-        ]
-        filter!(n->n!==nothing, nodes)
+            x += length(node)
+        end
+        if !isempty(run)
+            push!(walks, Walk(run, max(start, 0), x, max(-start, 0)))
+            append!(nodes, run)
+        end
 
         if length(nodes) == 0
             continue
@@ -165,30 +248,129 @@ function marshal_gfa(io::IO, G::Graph; opt=nothing)
             addlink!(nodes[end], nodes[1])
         end
 
-        paths[i] = Path(path.name, nodes, path.circular)
+        paths[i] = Path(path.name, nodes, path.circular, walks)
     end
 
     # export data
-    write(io, "H\tVN:Z:1.0\n")
+    write(io, "H\tVN:Z:$(version)\n")
 
     write(io, "# pancontigs\n")
     for segment in values(segments)
         segment === nothing && continue
 
-        write(io, "$(segment)\n")
+        emit(io, segment, version)
     end
 
     write(io, "# edges\n")
     for link in values(links)
-        write(io,"$(link)\n")
+        emit(io, link, version)
     end
 
     write(io, "# sequences\n")
     for i = 1:length(paths)
         if isassigned(paths, i)
-            write(io, "$(paths[i])\n")
+            emit(io, paths[i], version)
         end
     end
 end
 
+"""
+    unmarshal_gfa(io::IO)
+
+Deserialize the GFA 1 formatted input stream `io` into a Graph data structure.
+Each segment is converted to a block, each P-line or W-line to a path.
+Blocks carry no polymorphisms, i.e. all genomes that pass through a segment share its sequence.
+Genomes found both as P-line and W-line are merged, regardless of their order, and follow the W-line.
+Genomes split into several W-lines are concatenated in the order of their start coordinates.
+Circularity is read from the `TP:Z` tag of either line. The circular offset is set by the start coordinate of W-lines, shifted back by their `OF:i` tag.
+Segments that are not traversed by any genome are discarded.
+Return a `Graph` type.
+"""
+function unmarshal_gfa(io::IO)
+    blocks = Dict{String,Block}()
+    # genome name -> steps and topology from its P-line and W-line, merged regardless of their order
+    walks  = Dict{String,Dict{Symbol,Any}}()
+
+    tagged(tags, tag) = any(t == tag for t in tags)
+    offset(tags) = let
+        i = findfirst((t) -> startswith(t, "OF:i:"), tags)
+        i === nothing ? 0 : parse(Int, tags[i][6:end])
+    end
+
+    for line in eachline(io)
+        (isempty(line) || line[1] == '#') && continue
+        field = split(line, '\t')
+
+        if field[1] == "H"
+            for tag in field[2:end]
+                if startswith(tag, "VN:Z:") && startswith(tag[6:end], "2")
+                    error("GFA 2 input is not supported")
+                end
+            end
+        elseif field[1] == "S"
+            field[3] == "*" && error("segment '$(field[2])' does not store its sequence")
+            blocks[field[2]] = Block(
+                String(field[2]),
+                Array{UInt8}(field[3]),
+                Dict{Int,Int}(),
+                Dict{Nodes.Node{Block},SNPMap}(),
+                Dict{Nodes.Node{Block},InsMap}(),
+                Dict{Nodes.Node{Block},DelMap}(),
+            )
+        elseif field[1] == "P"
+            steps = map(split(field[3], ',')) do step
+                (!isempty(step) && step[end] ∈ ('+', '-')) || error("path '$(field[2])': invalid orientation of step '$(step)'")
+                (String(step[1:end-1]), step[end] == '+')
+            end
+
+            walk = get!(walks, String(field[2]), Dict{Symbol,Any}())
+            walk[:path]     = steps
+            walk[:circular] = get(walk, :circular, false) || tagged(field[5:end], "TP:Z:circular")
+        elseif field[1] == "W"
+            sample, haplotype, seqid, start = field[2], field[3], field[4], parse(Int, field[5])
+            name = seqid == sample ? String(sample) : "$(sample)#$(haplotype)#$(seqid)"
+
+            occursin(r"^([<>][^<>]+)+$", field[7]) || error("walk '$(name)': invalid steps '$(field[7])'")
+            steps = [(String(m[2]), m[1] == ">") for m in eachmatch(r"([<>])([^<>]+)", field[7])]
+
+            walk = get!(walks, name, Dict{Symbol,Any}())
+            push!(get!(walk, :walks, []), (start=start - offset(field[8:end]), steps=steps))
+            walk[:circular] = get(walk, :circular, false) || tagged(field[8:end], "TP:Z:circular")
+        end
+        # NOTE: links are implied by the paths and are thus ignored
+    end
+
+    paths = Dict(map(collect(walks)) do (name, walk)
+        steps, start = if haskey(walk, :walks)
+            runs = sort(walk[:walks]; by=(w)->w.start)
+            vcat((w.steps for w in runs)...), runs[1].start
+        else
+            walk[:path], 0
+        end
+
+        nodes = Nodes.Node{Block}[]
+        for (segment, strand) in steps
+            segment ∈ keys(blocks) || error("path '$(name)' traverses unknown segment '$(segment)'")
+            node = Nodes.Node{Block}(blocks[segment], strand)
+            append!(blocks[segment], node, nothing, nothing, nothing)
+            push!(nodes, node)
+        end
+
+        path = Paths.Path(
+            name,
+            nodes,
+            walk[:circular] ? start : nothing,
+            walk[:circular],
+            Int[],
+        )
+        Paths.positions!(path)
+
+        name => path
+    end)
+
+    filter!((blk) -> depth(last(blk)) > 0, blocks)
+
+    return Graph(blocks, paths)
+end
+
 end
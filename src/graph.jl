@@ -25,21 +25,31 @@ export reverse_complement, reverse_complement!
 function reverse_complement(item)  end
 function reverse_complement!(item) end
 
-export marshal, marshal_fasta, marshal_json, marshal_gfa
+export marshal, marshal_fasta, marshal_json, marshal_gfa, marshal_vcf, marshal_binary, marshal_bed, marshal_html
 function marshal_fasta(io::IO, x; opt=nothing) end
 function marshal_json(io::IO, x; opt=nothing) end
 function marshal_gfa(io::IO, x; opt=nothing) end
+function marshal_vcf(io::IO, x; opt=nothing) end
+function marshal_binary(io::IO, x; opt=nothing) end
+function marshal_bed(io::IO, x; opt=nothing) end
+function marshal_html(io::IO, x; opt=nothing) end
 
 function marshal(io::IO, x; fmt=:fasta, opt=nothing)
     @match fmt begin
         :fasta || :fa => return marshal_fasta(io, x; opt)
         :json         => return marshal_json(io, x; opt)
         :gfa          => return marshal_gfa(io, x; opt)
+        :vcf          => return marshal_vcf(io, x; opt)
+        :binary       => return marshal_binary(io, x; opt)
+        :bed          => return marshal_bed(io, x; opt)
+        :html         => return marshal_html(io, x; opt)
         _ => error("$fmt not a recognized output format")
     end
 end
 
-export unmarshal
+export unmarshal, unmarshal_gfa, unmarshal_binary
+function unmarshal_gfa end
+function unmarshal_binary end
 
 export serialize
 function serialize(io::IO, x) end
@@ -72,6 +82,60 @@ const DelMap = Dict{Int,Int}
 
 export Maybe, SNPMap, InsMap, DelMap
 
+"""
+    struct Annotation
+        id         :: String
+        isolate    :: String
+        source     :: String
+        type       :: String
+        start      :: Int
+        stop       :: Int
+        strand     :: Char
+        phase      :: String
+        attributes :: String
+        part       :: Int
+        parts      :: Int
+        length     :: Int
+    end
+
+A feature, e.g. a gene read from a GFF3 file, attached to a block.
+`start` and `stop` are 1-based, inclusive coordinates of the block consensus; `strand` is relative to the consensus.
+`isolate` is the genome the feature was annotated on and `length` its number of nucleotides within that genome.
+Features that span several blocks are stored as one `part` per block, out of `parts`, ordered along the annotated genome.
+"""
+struct Annotation
+    id         :: String
+    isolate    :: String
+    source     :: String
+    type       :: String
+    start      :: Int
+    stop       :: Int
+    strand     :: Char
+    phase      :: String
+    attributes :: String
+    part       :: Int
+    parts      :: Int
+    length     :: Int
+end
+
+# from its parsed json representation
+Annotation(a::AbstractDict) = Annotation(
+    a["id"], a["isolate"], a["source"], a["type"],
+    a["start"], a["stop"], a["strand"][1], a["phase"], a["attributes"],
+    a["part"], a["parts"], a["length"],
+)
+
+# copy of feature `a` moved to the consensus interval `start:stop` on `strand`
+Annotation(a::Annotation, start, stop, strand) = Annotation(
+    a.id, a.isolate, a.source, a.type,
+    start, stop, strand, a.phase, a.attributes,
+    a.part, a.parts, a.length,
+)
+
+flip(strand::Char) = strand == '+' ? '-' : strand == '-' ? '+' : strand
+
+export Annotation
+
 Base.show(io::IO, m::SNPMap) = show(io, [ k => Char(v) for (k,v) in m ])
 Base.show(io::IO, m::InsMap) = show(io, [ k => String(Base.copy(v)) for (k,v) in m ])
 
@@ -102,7 +166,7 @@ import .Shell: mash, mafft, havecommand
 import ..PanGraph: PanContigs
 
 export Graph
-export Shell, Blocks, Nodes, Utility
+export Shell, Blocks, Nodes, Utility, Alignments, PresenceAbsence, Schema, Regions, Annotations, Viewer, Figures, Summary, Accumulation, Marginals, Phylogeny, Server
 
 export graphs, detransitive!, purge!, prune!, finalize!
 export pancontigs
@@ -115,22 +179,47 @@ export checkblocks
     struct Graph
         block    :: Dict{String, Block}
         sequence :: Dict{String, Path}
+        metadata :: Dict{String, Any}
+        annotation :: Dict{String, Array{Annotation,1}}
     end
 
 Representation of a multiple sequence alignment. Alignments of homologous sequences
 are stored as blocks. A genome is stored as a path, i.e. a list of blocks.
+Graph-level `metadata`, such as build parameters and input checksums, is carried along when serialized.
+Features attached to blocks are stored in `annotation`, keyed by block `uuid`.
 """
 struct Graph
-    block    :: Dict{String,Block}   # uuid      -> block
-    sequence :: Dict{String,Path}    # isolation -> path
+    block      :: Dict{String,Block}                # uuid      -> block
+    sequence   :: Dict{String,Path}                 # isolation -> path
+    metadata   :: Dict{String,Any}                  # provenance
+    annotation :: Dict{String,Array{Annotation,1}}  # uuid      -> features
     # TODO: add edge/junction data structure?
 end
 
+Graph(block, sequence) = Graph(block, sequence, Dict{String,Any}())
+Graph(block, sequence, metadata) = Graph(block, sequence, metadata, Dict{String,Array{Annotation,1}}())
+
 include("align.jl")
 using .Align
 
 # export file formats
 include("gfa.jl")
+include("vcf.jl")
+include("bed.jl")
+include("viewer.jl")
+include("figure.jl")
+include("summary.jl")
+include("accumulation.jl")
+include("marginal.jl")
+include("binary.jl")
+include("schema.jl")
+include("alignments.jl")
+include("presence.jl")
+include("region.jl")
+using .Regions: liftover
+include("annotation.jl")
+include("phylogeny.jl")
+include("server.jl")
 
 # --------------------------------
 # constructors
@@ -262,6 +351,21 @@ function detransitive!(G::Graph)
             pop!(G.block, b.uuid)
         end
 
+        # carry attached features over to the concatenated consensus
+        features = Annotation[]
+        δ = 0
+        for (b, s) ∈ c
+            for a in get(G.annotation, b.uuid, Annotation[])
+                push!(features, s ?
+                    Annotation(a, δ+a.start, δ+a.stop, a.strand) :
+                    Annotation(a, δ+length(b)-a.stop+1, δ+length(b)-a.start+1, flip(a.strand))
+                )
+            end
+            delete!(G.annotation, b.uuid)
+            δ += length(b)
+        end
+        isempty(features) || (G.annotation[new.uuid] = features)
+
         G.block[new.uuid] = new
     end
 end
@@ -275,8 +379,16 @@ Internal function used during guide tree alignment.
 function prune!(G::Graph)
     used = Set(n.block.uuid for p in values(G.sequence) for n in p.node)
     filter!((blk)->first(blk) ∈ used, G.block)
+    prune_annotation!(G)
 end
 
+"""
+    prune_annotation!(G::Graph)
+
+Remove the features attached to blocks that are no longer stored in graph `G`.
+"""
+prune_annotation!(G::Graph) = filter!((a)->first(a) ∈ keys(G.block), G.annotation)
+
 """
     purge!(G::Graph)
 
@@ -323,6 +435,7 @@ function keeponly!(G::Graph, names::T...) where T <: AbstractString
         end
         # TODO: reconsensus?
     end
+    prune_annotation!(G)
 end
 
 function checkblocks(G::Graph)
@@ -384,7 +497,7 @@ end
 
 Serialize graph `G` as a json format output stream `io`.
 This is the main storage/exported format for PanGraph.
-Currently it is the only format that can reconstruct an in-memory pangraph.
+Along with `marshal_binary`, it is the only format that can fully reconstruct an in-memory pangraph.
 
 `opt` is currently ignored. It is kept for signature uniformity for other marshal functions
 """
@@ -440,7 +553,8 @@ function marshal_json(io::IO, G::Graph; opt=nothing)
             mutate    = [(strip(nodes[key]), pack(val)) for (key,val) ∈ b.mutate],
             insert    = [(strip(nodes[key]), pack(val)) for (key,val) ∈ b.insert],
             delete    = [(strip(nodes[key]), pack(val)) for (key,val) ∈ b.delete],
-            positions = [(strip(key), val) for (key,val) ∈ positions[b]]
+            positions = [(strip(key), val) for (key,val) ∈ positions[b]],
+            annotations = get(G.annotation, b.uuid, Annotation[]),
         )
     end
 
@@ -449,21 +563,28 @@ function marshal_json(io::IO, G::Graph; opt=nothing)
     blocks = [ dict(block) for block ∈ values(G.block) ]
 
     JSON.print(io, (
-        paths  = paths,
-        blocks = blocks,
+        version  = Schema.CURRENT,
+        metadata = G.metadata,
+        paths    = paths,
+        blocks   = blocks,
     ))
 end
 
-# NOTE: only recognizes json input right now
 """
     unmarshal(io::IO)
 
 Deserialize the json formatted input stream `io` into a Graph data structure.
+Files written by older versions of PanGraph are upgraded to the current schema first.
+Malformed input raises an error that lists all schema violations.
 Return a `Graph` type.
 """
 function unmarshal(io)
     graph = JSON.parse(io)
 
+    Schema.migrate!(graph)
+    violations = Schema.validate(graph)
+    isempty(violations) || error(Schema.report(violations))
+
     unpack = (
         snp = Dict(),
         ins = Dict(),
@@ -504,7 +625,7 @@ function unmarshal(io)
             offset   = path["offset"],
             circular = path["circular"],
             blocks   = path["blocks"],
-            position = "position" ∈ keys(path) ? path["position"] : [], # to ease upgrade from old pangraph files
+            position = path["position"], # NOTE: may be empty for files upgraded from version 1
         )
 
         nodes = Node{Block}[]
@@ -549,7 +670,13 @@ function unmarshal(io)
         p.name => path
     end)
 
-    return Graph(blocks, paths)
+    annotation = Dict{String,Array{Annotation,1}}()
+    for blk in graph["blocks"]
+        isempty(blk["annotations"]) && continue
+        annotation[String(blk["id"])] = [Annotation(a) for a in blk["annotations"]]
+    end
+
+    return Graph(blocks, paths, Dict{String,Any}(graph["metadata"]), annotation)
 end
 
 # ------------------------------------------------------------------------
@@ -574,6 +701,102 @@ Return all pairs of `name` => `sequence` encoded within graph `G`
 """
 sequence(g::Graph) = [ name => join(String(sequence(node.block, node)) for node ∈ path.node) for (name, path) ∈ g.sequence ]
 
+# contiguous runs of loci at which two sequences differ, including any length difference
+function mismatches(ref, seq)
+    runs = UnitRange{Int}[]
+    for x in 1:min(length(ref), length(seq))
+        ref[x] == seq[x] && continue
+        if !isempty(runs) && last(runs[end]) == x-1
+            runs[end] = first(runs[end]):x
+        else
+            push!(runs, x:x)
+        end
+    end
+    if length(ref) != length(seq)
+        push!(runs, (min(length(ref), length(seq))+1):max(length(ref), length(seq)))
+    end
+
+    return runs
+end
+
+# blocks of path `p` whose nodes overlap any of the loci `runs`
+function overlapping(p::Path, runs)
+    L = genomelength(p)
+    blocks = Set{String}()
+    for (i, node) in enumerate(p.node)
+        (len = length(node)) > 0 || continue
+        start = p.position[i]
+        stop  = start + len - 1
+        intervals = stop ≤ L ? [start:stop] : [start:L, 1:(stop-L)]
+        if any(!isempty(intersect(r, I)) for r in runs for I in intervals)
+            push!(blocks, node.block.uuid)
+        end
+    end
+
+    return sort(collect(blocks))
+end
+
+"""
+    validate(G::Graph, reference)
+
+Verify that graph `G` faithfully encodes the genomes of `reference`, a map from isolate name to sequence.
+Every path is rebuilt, restoring its circular offset, and compared to its reference genome.
+Every block is checked for internal consistency by `check`.
+Return a report of the isolates missing from either side, of the mismatching isolates along with the mismatching loci and the blocks that overlap them,
+and of all inconsistent blocks.
+"""
+function validate(G::Graph, reference)
+    absent = (
+        graph = sort([name for name in keys(reference) if name ∉ keys(G.sequence)]),
+        input = sort([name for name in keys(G.sequence) if name ∉ keys(reference)]),
+    )
+
+    isolates   = sort([name for name in keys(G.sequence) if name ∈ keys(reference)])
+    mismatched = []
+    for name in isolates
+        path = G.sequence[name]
+        ref  = reference[name]
+        seq  = Array{UInt8}(sequence(path))
+
+        runs = mismatches(ref, seq)
+        isempty(runs) && continue
+
+        push!(mismatched, (
+            isolate = name,
+            length  = (expected = length(ref), observed = length(seq)),
+            loci    = [[first(r), last(r)] for r in runs],
+            blocks  = overlapping(path, runs),
+        ))
+    end
+
+    inconsistent = []
+    used = Set(node.block.uuid for path in values(G.sequence) for node in path.node)
+    for uuid in sort(collect(union(used, keys(G.block))))
+        problem = if uuid ∉ keys(G.block)
+            "referenced by a path but not stored"
+        elseif uuid ∉ used
+            "stored but not referenced by any path"
+        else
+            try
+                # NOTE: check prints its diagnostics to stdout, which would corrupt the report
+                redirect_stdout(() -> check(G.block[uuid]), devnull)
+                nothing
+            catch err
+                sprint(showerror, err)
+            end
+        end
+
+        problem === nothing || push!(inconsistent, (block = uuid, error = problem))
+    end
+
+    return (
+        valid      = isempty(absent.graph) && isempty(mismatched) && isempty(inconsistent),
+        isolates   = (checked = length(isolates), absent = absent),
+        mismatches = mismatched,
+        blocks     = inconsistent,
+    )
+end
+
 """
     realign!(G::Graph; accept)
 
@@ -583,6 +806,11 @@ Usage of this function requires [MAFFT](https://mafft.cbrc.jp/alignment/software
 By default, all blocks are realigned.
 """
 function realign!(g::Graph; accept=(_)->true, case=false)
+    # NOTE: realignment changes consensus coordinates, which invalidates attached features
+    for blk in values(g.block)
+        accept(blk) && delete!(g.annotation, blk.uuid)
+    end
+
     meter = Progress(length(g.block); desc="polishing progress", output=stderr)
     Threads.@threads for blk in collect(values(g.block))
         if !accept(blk)
@@ -0,0 +1,76 @@
+Growth = Command(
+   "growth",
+   "pangraph growth <options> [arguments]",
+   "computes pangenome and core genome accumulation curves of a multiple sequence alignment graph",
+   """zero or one pangraph file (json, binary or gfa)
+      if no file, reads from stdin
+      stream can be optionally gzipped.""",
+   [
+    Arg(
+        Int,
+        "number of orderings",
+        (short="-n", long="--orderings"),
+        "number of random orderings of the isolates",
+        10,
+    ),
+    Arg(
+        Int,
+        "random seed",
+        (short="-r", long="--random-seed"),
+        "seed of the random orderings",
+        0,
+    ),
+    Arg(
+        String,
+        "output path",
+        (short="-o", long="--output"),
+        "path to store the tab-separated curves\n\tif empty, the curves are written to stdout",
+        "",
+    ),
+    Arg(
+        String,
+        "fit path",
+        (short="-H", long="--heaps"),
+        "path to store the json formatted fit of Heaps' law to the pangenome curve\n\tif empty, will skip this computation",
+        "",
+    ),
+   ],
+
+   function(args)
+       path = parse(Growth, args)
+       path = if (path === nothing || length(path) == 0)
+           nothing
+       elseif length(path) == 1
+           path
+       else
+           usage(Growth)
+           return 2
+       end
+
+       n = arg(Growth, "-n")
+       n ≥ 1 || panic("number of orderings must be positive\n")
+
+       graph = load(path, Growth)
+       curve = Graphs.Accumulation.curves(graph, n; seed=arg(Growth, "-r"))
+
+       output = arg(Growth, "-o")
+       if length(output) > 0
+           Base.open(output, "w") do io
+               Graphs.Accumulation.write_curves(io, curve)
+           end
+       else
+           Graphs.Accumulation.write_curves(stdout, curve)
+       end
+
+       fit = arg(Growth, "-H")
+       if length(fit) > 0
+           heaps = Graphs.Accumulation.heaps(curve)
+           heaps === nothing && @warn "too few isolates with new sequence to fit Heaps' law"
+           Base.open(fit, "w") do io
+               JSON.print(io, heaps, 2)
+           end
+       end
+
+       return 0
+   end
+)
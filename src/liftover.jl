@@ -0,0 +1,107 @@
+Liftover = Command(
+   "liftover",
+   "pangraph liftover <options> [arguments]",
+   "converts positions of one isolate to the homologous positions of other isolates",
+   """zero or one pangraph file (json, binary or gfa)
+      if no file, reads from stdin
+      stream can be optionally gzipped.""",
+   [
+    Arg(
+        String,
+        "source isolate",
+        (short="-s", long="--source"),
+        "name of the isolate the positions refer to",
+        "",
+    ),
+    Arg(
+        String,
+        "target isolates",
+        (short="-t", long="--target"),
+        "isolates to convert positions to\n\tcomma seperated list, no spaces\n\tif empty, all isolates are used",
+        "",
+    ),
+    Arg(
+        String,
+        "positions",
+        (short="-p", long="--positions"),
+        "positions to convert, 1-based and relative to the original rotation of the source isolate\n\tcomma seperated list, no spaces",
+        "",
+    ),
+    Arg(
+        String,
+        "positions file",
+        (short="-P", long="--positions-file"),
+        "path to file with one position to convert per line",
+        "",
+    ),
+   ],
+
+   function(args)
+       path = parse(Liftover, args)
+       path = if (path === nothing || length(path) == 0)
+           nothing
+       elseif length(path) == 1
+           path
+       else
+           usage(Liftover)
+           return 2
+       end
+
+       source = arg(Liftover, "-s")
+       if length(source) == 0
+           usage(Liftover)
+           return 2
+       end
+
+       positions = String[]
+       length(arg(Liftover, "-p")) > 0 && append!(positions, split(arg(Liftover, "-p"), ','))
+       if length(arg(Liftover, "-P")) > 0
+           file = arg(Liftover, "-P")
+           isfile(file) || panic("file '$(file)' not found\n")
+           append!(positions, filter(!isempty, strip.(readlines(file))))
+       end
+       if length(positions) == 0
+           usage(Liftover)
+           return 2
+       end
+       positions = map(positions) do pos
+           x = tryparse(Int, pos)
+           x === nothing && panic("position '$(pos)' is not an integer\n")
+           x
+       end
+
+       graph = load(path, Liftover)
+       source ∈ keys(graph.sequence) || panic("isolate '$(source)' not found in pangraph\n")
+
+       L = Graphs.genomelength(graph.sequence[source])
+       for pos in positions
+           1 ≤ pos ≤ L || panic("position $(pos) lies outside of '$(source)' of length $(L)\n")
+       end
+
+       targets = arg(Liftover, "-t")
+       targets = length(targets) > 0 ? split(targets, ',') : sort(collect(keys(graph.sequence)))
+       for target in targets
+           target ∈ keys(graph.sequence) || panic("isolate '$(target)' not found in pangraph\n")
+       end
+
+       cache = Dict{Graphs.Block,Any}()
+       println(stdout, join(["source", "position", "target", "block", "copy", "target_position", "strand"], '\t'))
+       for pos in positions, target in targets
+           hits = Graphs.liftover(graph, source, pos, target; cache=cache)
+           if isempty(hits)
+               println(stdout, join([source, pos, target, ".", ".", ".", "."], '\t'))
+               continue
+           end
+
+           for hit in hits
+               println(stdout, join([
+                   source, pos, target, hit.block, hit.copy,
+                   hit.position === nothing ? "." : hit.position,
+                   hit.strand ? "+" : "-",
+               ], '\t'))
+           end
+       end
+
+       return 0
+   end
+)
@@ -0,0 +1,184 @@
+module Marginals
+
+using Rematch
+
+import ..Graphs:
+    Graph, Block, Node,
+    count_isolates, junctions, left, right
+
+export pairwise, write_matrix
+
+# ------------------------------------------------------------------------
+# single pair
+
+# NOTE: blocks joined by a transitive junction are merged by detransitive!
+#       the number of blocks left is the number of connected components of blocks linked by transitive junctions
+function components(blocks, links)
+    parent = Dict(b => b for b in blocks)
+    root(b) = parent[b] == b ? b : (parent[b] = root(parent[b]))
+    for (b₁, b₂) in links
+        r₁, r₂ = root(b₁), root(b₂)
+        r₁ == r₂ || (parent[r₁] = r₂)
+    end
+    return count(b -> root(b) == b, blocks)
+end
+
+# number of blocks and edges left after marginalizing onto `paths` and removing transitive junctions
+function structure(paths, counts)
+    links = junctions(paths)
+    transitive = [
+        (left(j).block, right(j).block) for (j, n) in links
+        if counts[left(j).block] == counts[right(j).block] == n
+    ]
+    return (blocks=components(collect(keys(counts)), transitive), edges=length(links) - length(transitive))
+end
+
+# loci of the consensus of block `b` deleted within `node`
+function deleted(b::Block, node::Node)
+    loci = BitSet()
+    for (x, len) in b.delete[node]
+        union!(loci, x:(x+len-1))
+    end
+    return loci
+end
+
+# number of loci of block `b` compared between nodes `n₁` and `n₂`, along with the number of those that differ
+# NOTE: loci deleted in either node are not compared; insertions are ignored
+function snps(b::Block, n₁::Node, n₂::Node)
+    gaps = union(deleted(b, n₁), deleted(b, n₂))
+    m₁, m₂ = b.mutate[n₁], b.mutate[n₂]
+
+    differ = 0
+    for x in union(keys(m₁), keys(m₂))
+        x ∈ gaps && continue
+        differ += get(m₁, x, b.sequence[x]) != get(m₂, x, b.sequence[x])
+    end
+
+    return (sites=length(b) - length(gaps), differ=differ)
+end
+
+"""
+    marginal(G::Graph, name₁, name₂)
+
+Return the statistics of the marginalization of graph `G` onto the pair of isolates `name₁` and `name₂`, without copying `G`.
+The number of `blocks` and `edges` are those left after `keeponly!` followed by `detransitive!`.
+`shared` is the total consensus length of blocks found in both isolates, `private` the length of each isolate found in blocks absent from the other.
+`sites` and `differ` count the loci compared within shared blocks and the SNPs among them, over all pairs of copies.
+"""
+function marginal(G::Graph, name₁, name₂)
+    paths  = [G.sequence[name₁], G.sequence[name₂]]
+    counts = count_isolates(paths)
+    shared = [b for b in keys(counts) if length(counts[b]) == 2]
+
+    private = map(paths) do p
+        sum((length(node) for node in p.node if length(counts[node.block]) == 1); init=0)
+    end
+
+    copies = map(paths) do p
+        nodes = Dict{Block,Array{Node{Block},1}}()
+        for node in p.node
+            push!(get!(nodes, node.block, Node{Block}[]), node)
+        end
+        nodes
+    end
+
+    sites, differ = 0, 0
+    for b in shared, n₁ in copies[1][b], n₂ in copies[2][b]
+        s = snps(b, n₁, n₂)
+        sites  += s.sites
+        differ += s.differ
+    end
+
+    graph = structure(paths, counts)
+    return (
+        blocks  = graph.blocks,
+        edges   = graph.edges,
+        shared  = sum((length(b) for b in shared); init=0),
+        private = (private[1], private[2]),
+        sites   = sites,
+        differ  = differ,
+    )
+end
+
+# ------------------------------------------------------------------------
+# all pairs
+
+"""
+    pairwise(G::Graph)
+
+Compute the statistics of the marginalization of graph `G` onto every pair of isolates, see `marginal`, without copying `G`.
+Return the sorted isolate names along with a named tuple of square matrices indexed by isolate:
+  - `blocks`: number of blocks after marginalization.
+  - `edges`: number of edges, i.e. distinct junctions between blocks, after marginalization.
+  - `shared`: total consensus length of the blocks shared by both isolates.
+  - `private`: length of the row isolate found in blocks absent from the column isolate. This matrix is not symmetric.
+  - `divergence`: fraction of compared loci of shared blocks that differ by a SNP, `NaN` if no locus is compared.
+Diagonal entries are computed for the marginalization onto a single isolate.
+"""
+function pairwise(G::Graph)
+    names = sort(collect(keys(G.sequence)))
+    N     = length(names)
+
+    matrix(T) = zeros(T, N, N)
+    M = (
+        blocks     = matrix(Int),
+        edges      = matrix(Int),
+        shared     = matrix(Int),
+        private    = matrix(Int),
+        divergence = matrix(Float64),
+    )
+
+    pairs = [(i, j) for i in 1:N for j in i:N]
+    Threads.@threads for (i, j) in pairs
+        if i == j
+            paths  = [G.sequence[names[i]]]
+            counts = count_isolates(paths)
+            graph  = structure(paths, counts)
+
+            M.blocks[i,i] = graph.blocks
+            M.edges[i,i]  = graph.edges
+            M.shared[i,i] = sum((length(b) for b in keys(counts)); init=0)
+            continue
+        end
+
+        m = marginal(G, names[i], names[j])
+        M.blocks[i,j]     = M.blocks[j,i]     = m.blocks
+        M.edges[i,j]      = M.edges[j,i]      = m.edges
+        M.shared[i,j]     = M.shared[j,i]     = m.shared
+        M.divergence[i,j] = M.divergence[j,i] = m.sites > 0 ? m.differ/m.sites : NaN
+        M.private[i,j], M.private[j,i] = m.private
+    end
+
+    return names, M
+end
+
+"""
+    write_matrix(io::IO, names, M; fmt=:tsv)
+
+Output the square matrix `M`, indexed by isolate `names`, to IO stream `io`.
+`fmt` can be either `:tsv`, in which case the first row and column hold the isolate names,
+or `:phylip`, in which case the square distance matrix format of PHYLIP is used.
+Undefined entries, i.e. `NaN`, are written as `NA` in TSV and as `-1` in PHYLIP, whose readers only accept numbers.
+"""
+function write_matrix(io::IO, names, M; fmt=:tsv)
+    @match fmt begin
+        :tsv => begin
+            entry = (x) -> (x isa AbstractFloat && isnan(x)) ? "NA" : x
+            write(io, join(["", names...], '\t'), '\n')
+            for (i, name) in enumerate(names)
+                write(io, join([name, entry.(M[i,:])...], '\t'), '\n')
+            end
+        end
+        :phylip => begin
+            entry = (x) -> (x isa AbstractFloat && isnan(x)) ? -1 : x
+            write(io, "$(length(names))\n")
+            width = max(10, maximum(length.(names); init=0))
+            for (i, name) in enumerate(names)
+                write(io, rpad(name, width), ' ', join(entry.(M[i,:]), ' '), '\n')
+            end
+        end
+        _ => error("$fmt not a recognized matrix format")
+    end
+end
+
+end
@@ -2,7 +2,7 @@ Marginalize = Command(
    "marginalize",
    "pangraph marginalize <options> [arguments]",
    "computes all pairwise marginalizations of a multiple sequence alignment graph",
-   """multiple sequence alignment accepted in formats: [json]""",
+   """multiple sequence alignment accepted in formats: [json, binary, gfa]""",
    [
     Arg(
         String,
@@ -25,6 +25,27 @@ Marginalize = Command(
         "collapse the graph to only blocks contained by paths of the given isolates.\n\tcomma seperated list, no spaces",
         "",
     ),
+    Arg(
+        String,
+        "matrices path",
+        (short="-m", long="--matrices"),
+        "path to directory where matrices of pairwise marginalization statistics will be stored\n\tno marginal graph is written and paralogs are not reduced. if empty, will skip this computation",
+        "",
+    ),
+    Arg(
+        String,
+        "matrix format",
+        (short="-mf", long="--matrix-format"),
+        "file format of the pairwise matrices\n\trecognized options: [tsv, phylip]",
+        "tsv",
+    ),
+    Arg(
+        String,
+        "output format",
+        (short="-f", long="--format"),
+        "format of the output pangraph\n\trecognized options: [json, binary]",
+        "json",
+    ),
    ],
    (args) -> let
        path = parse(Marginalize, args)
@@ -36,11 +57,14 @@ Marginalize = Command(
            return 2
        end
 
-       graph  = load(path, Marginalize)
-       names  = collect(keys(graph.sequence))
+       graph   = load(path, Marginalize)
+       names   = collect(keys(graph.sequence))
+       inputs  = path === nothing ? [] : path
+       history = [copy(graph.metadata)]
 
        reduce = arg(Marginalize, "-r")
        output = arg(Marginalize, "-o")
+       fmt    = outputformat(Marginalize)
 
        if length(output) > 0
            isdir(output) || mkpath(output)
@@ -63,8 +87,32 @@ Marginalize = Command(
 
                # recompute positions
                Graphs.finalize!(G)
-               open("$(output)/$(name₁)-$(name₂).json", "w") do io
-                   marshal(io, G; fmt=:json)
+               provenance!(G, Marginalize, inputs; history=history)
+               open("$(output)/$(filename(name₁))-$(filename(name₂)).$(extension(fmt))", "w") do io
+                   marshal(io, G; fmt=fmt)
+               end
+           end
+       end
+
+       matrices = arg(Marginalize, "-m")
+       if length(matrices) > 0
+           format, suffix = @match arg(Marginalize, "-mf") begin
+               "tsv"    => (:tsv, "tsv")
+               "phylip" => (:phylip, "phy")
+                _       => begin
+                    usage(Marginalize)
+                    exit(1)
+                end
+           end
+
+           # NOTE: statistics are computed on the input graph without copying it, thus paralogs cannot be reduced
+           reduce && @warn "paralog reduction does not apply to the matrices of pairwise statistics"
+
+           isdir(matrices) || mkpath(matrices)
+           strains, M = Graphs.Marginals.pairwise(graph)
+           for key in keys(M)
+               open("$(matrices)/$(key).$(suffix)", "w") do io
+                   Graphs.Marginals.write_matrix(io, strains, M[key]; fmt=format)
                end
            end
        end
@@ -88,7 +136,8 @@ Marginalize = Command(
            
            # recompute positions
            Graphs.finalize!(graph)
-           marshal(stdout, graph; fmt=:json)
+           provenance!(graph, Marginalize, inputs; history=history)
+           marshal(stdout, graph; fmt=fmt)
        end
    end
 )
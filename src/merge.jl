@@ -0,0 +1,103 @@
+Merge = Command(
+   "merge",
+   "pangraph merge <options> [arguments]",
+   "merges independently built multiple sequence alignment graphs",
+   """two or more pangraph files (json, binary or gfa).
+      files can be optionally gzipped.
+      isolate names must be unique across all files.""",
+   [
+    Arg(
+        Int,
+        "minimum length",
+        (short="-l", long="--len"),
+        "minimum block size for alignment graph (in nucleotides)",
+        100,
+    ),
+    Arg(
+        Float64,
+        "block junction cost",
+        (short="-a", long="--alpha"),
+        "energy cost for introducing junction due to alignment merger",
+        100,
+    ),
+    Arg(
+        Float64,
+        "block diversity cost",
+        (short="-b", long="--beta"),
+        "energy cost for interblock diversity due to alignment merger",
+        10,
+    ),
+    Arg(
+        String,
+        "pairwise sensitivity",
+        (short="-s", long="--sensitivity"),
+        "used to set pairwise alignment sensitivity\n\trecognized options: [5, 10, 20]",
+        "10",
+    ),
+    Arg(
+        Int,
+        "maximum self maps",
+        (short="-x", long="--max-self-map"),
+        "maximum number of self mappings to consider per pairwise graph merger",
+        100,
+    ),
+    Arg(
+        String,
+        "alignment kernel",
+        (short="-k", long="--alignment-kernel"),
+        "backend to use for pairwise genome alignment\n\trecognized options: [minimap2, mmseqs]",
+        "minimap2",
+    ),
+    Arg(
+        Int,
+        "k-mer length",
+        (short="-K", long="--kmer-length"),
+        "kmer length, only used for mmseqs2 alignment kernel. If not specified will use mmseqs default.",
+        0,
+    ),
+    Arg(
+        String,
+        "output format",
+        (short="-f", long="--format"),
+        "format of the output pangraph\n\trecognized options: [json, binary]",
+        "json",
+    ),
+   ],
+
+   function(args)
+       files = parse(Merge, args)
+       if files === nothing || length(files) < 2
+           usage(Merge)
+           return 2
+       end
+       fmt = outputformat(Merge)
+
+       inputs  = [load([file], Merge) for file in files]
+       history = [copy(G.metadata) for G in inputs]
+
+       minblock = arg(Merge, "-l")
+       energy   = alignment_energy(minblock, arg(Merge, "-a"), arg(Merge, "-b"))
+       maxiter  = arg(Merge, "-x")
+
+       sensitivity = alignment_sensitivity(arg(Merge, "-s"))
+       if sensitivity === nothing
+           usage(Merge)
+           exit(1)
+       end
+
+       aligner = alignment_kernel(arg(Merge, "-k"), minblock, sensitivity, arg(Merge, "-K"))
+
+       before = union(attached.(inputs)...)
+       graph  = merge(aligner, inputs...;
+            energy      = energy,
+            minblock    = minblock,
+            maxiter     = maxiter,
+       )
+       finalize!(graph)
+       warnfeatures(before, graph)
+       provenance!(graph, Merge, files; history=history)
+
+       marshal(stdout, graph; fmt=fmt)
+       return 0
+   end
+)
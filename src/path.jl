@@ -11,7 +11,7 @@ import ..Graphs:
     Counter, add!
 
 export Path
-export count_isolates, positions!
+export count_isolates, positions!, genomelength, copyindex
 
 """
 	mutable struct Path
@@ -61,6 +61,24 @@ Return the number of nodes associated to Path `p`.
 """
 length(p::Path) = length(p.node)
 
+"""
+	genomelength(p::Path)
+
+Return the number of nucleotides of the genome represented by Path `p`.
+"""
+genomelength(p::Path) = sum(length(n) for n in p.node; init=0)
+
+"""
+	copyindex(p::Path)
+
+Return the copy number of each node of Path `p`, i.e. how many times its block
+has been traversed along `p` up to and including the node.
+"""
+function copyindex(p::Path)
+    seen = Dict{Block,Int}()
+    return [seen[n.block] = get(seen, n.block, 0) + 1 for n in p.node]
+end
+
 """
 	sequence(p::Path; shift=true)
 
@@ -0,0 +1,174 @@
+module Phylogeny
+
+import ..Graphs:
+    Graph, Block,
+    alignment, count_isolates
+
+import ..Align: Clade, nj, isleaf, newick
+import ..Alignments
+
+export distance, informative, splits, incongruence
+
+# ------------------------------------------------------------------------
+# trees
+
+const GAP = (UInt8('-'), UInt8('N'), UInt8('n'))
+
+"""
+    distance(aln)
+
+Return the matrix of pairwise p-distances between the sequences of the alignment `aln`, stored as one column per sequence.
+Sites with a gap or an ambiguous nucleotide in either sequence are not compared; sequences without any compared site are at distance 1.
+"""
+function distance(aln)
+    n = size(aln, 2)
+    D = zeros(Float64, n, n)
+    for i in 1:n, j in (i+1):n
+        compared, differ = 0, 0
+        for (a, b) in zip(view(aln,:,i), view(aln,:,j))
+            (a ∈ GAP || b ∈ GAP) && continue
+            compared += 1
+            differ   += uppercase(Char(a)) != uppercase(Char(b))
+        end
+        D[i,j] = D[j,i] = compared > 0 ? differ/compared : 1.0
+    end
+    return D
+end
+
+"""
+    informative(aln)
+
+Return true if the alignment `aln`, stored as one column per sequence, holds a parsimony-informative site,
+i.e. a site with at least two nucleotides each found in at least two sequences.
+Trees of alignments without such a site have no supported non-trivial bipartition.
+"""
+function informative(aln)
+    for site in eachrow(aln)
+        count = Dict{Char,Int}()
+        for c in site
+            c ∈ GAP && continue
+            nuc = uppercase(Char(c))
+            count[nuc] = get(count, nuc, 0) + 1
+        end
+        sum(n ≥ 2 for n in values(count); init=0) ≥ 2 && return true
+    end
+    return false
+end
+
+# NOTE: nj requires at least two leaves
+tree(D, names) = length(names) == 1 ? Clade(names[1]) : nj(copy(D), names)
+
+# leaf names below every node of the tree rooted at `c`
+function clusters(c::Clade)
+    result = Set{String}[]
+    function traverse(node)
+        if isleaf(node)
+            leaf = Set([node.name])
+            push!(result, leaf)
+            return leaf
+        end
+        below = union(traverse(node.left), traverse(node.right))
+        push!(result, below)
+        return below
+    end
+    traverse(c)
+    return result
+end
+
+"""
+    splits(c::Clade; within=nothing)
+
+Return the non-trivial bipartitions of the leaves of the unrooted tree `c`, restricted to the leaves `within` if given.
+Each bipartition is represented by the sorted names of the side that does not contain the first leaf in alphabetical order.
+"""
+function splits(c::Clade; within=nothing)
+    clades = clusters(c)
+    leaves = within === nothing ? clades[end] : Set(within)
+    n      = length(leaves)
+    ref    = minimum(leaves)
+
+    result = Set{Vector{String}}()
+    for clade in clades
+        side = intersect(clade, leaves)
+        side = ref ∈ side ? setdiff(leaves, side) : side
+        2 ≤ length(side) ≤ n-2 && push!(result, sort(collect(side)))
+    end
+    return result
+end
+
+# ------------------------------------------------------------------------
+# incongruence
+
+"""
+    incongruence(G::Graph; mindepth=3, threshold=0.5, core=1.0)
+
+Compare the neighbor-joining tree of every block of graph `G` to the neighbor-joining tree of the core genome alignment.
+The core genome is made of the blocks found once in at least a fraction `core` of the isolates, see `Alignments.core`.
+Only blocks found in at least `mindepth` isolates, at most once in each, are compared; blocks without a parsimony-informative site, see `informative`,
+have an arbitrary topology and are not scored. Trees are built from p-distances, see `distance`; the core tree is restricted to the isolates of each block.
+
+Return the core tree along with an array with one named tuple per block found in at least `mindepth` isolates, holding the `block`,
+its `tree`, the number of `isolates`, the Robinson-Foulds distance `rf` between both trees and its normalized `score`,
+i.e. `rf` divided by its maximum `2(n-3)` for `n` isolates, whether the block is `incongruent`, i.e. its score exceeds `threshold`, and its `status`:
+`:compared`, `:uninformative` or `:duplicated` if found more than once in an isolate. The `tree`, `rf` and `score` of blocks not compared are `nothing`.
+Blocks are sorted by decreasing score, followed by blocks not compared. Blocks with three isolates always have a score of zero.
+"""
+function incongruence(G::Graph; mindepth=3, threshold=0.5, core=1.0)
+    mindepth ≥ 3 || error("trees with less than three isolates have no topology")
+    0 < core ≤ 1 || error("core threshold must lie within (0, 1]")
+
+    names, aln = Alignments.core(G; threshold=core)
+    size(aln, 1) > 0 || error("no core blocks found at threshold $(core) to build the core genome tree")
+    reference  = tree(distance(aln), names)
+
+    owner  = Dict(node => name for (name, path) in G.sequence for node in path.node)
+    blocks = [(b, count) for (b, count) in count_isolates(values(G.sequence)) if length(count) ≥ mindepth]
+
+    result = Vector{Any}(undef, length(blocks))
+    Threads.@threads for i in 1:length(blocks)
+        b, count = blocks[i]
+        skipped  = (status) -> (
+            block       = b,
+            tree        = nothing,
+            isolates    = length(count),
+            rf          = nothing,
+            score       = nothing,
+            incongruent = false,
+            status      = status,
+        )
+
+        if any(c > 1 for c in values(count))
+            result[i] = skipped(:duplicated)
+            continue
+        end
+
+        A, nodes, _ = alignment(b)
+        if !informative(A)
+            result[i] = skipped(:uninformative)
+            continue
+        end
+
+        leaves = [owner[node] for node in nodes]
+        T = tree(distance(A), leaves)
+
+        S₁ = splits(T)
+        S₂ = splits(reference; within=leaves)
+        rf = length(symdiff(S₁, S₂))
+        n  = length(leaves)
+
+        score = n > 3 ? rf/(2*(n-3)) : 0.0
+        result[i] = (
+            block       = b,
+            tree        = T,
+            isolates    = n,
+            rf          = rf,
+            score       = score,
+            incongruent = score > threshold,
+            status      = :compared,
+        )
+    end
+
+    return reference, sort(result; by=(r) -> (r.score === nothing, r.score === nothing ? 0.0 : -r.score, r.block.uuid))
+end
+
+end
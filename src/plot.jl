@@ -0,0 +1,129 @@
+Plot = Command(
+   "plot",
+   "pangraph plot <options> [arguments]",
+   "draws the genomes of a multiple sequence alignment graph as rows of blocks in a scalable vector graphic",
+   """zero or one pangraph file (json, binary or gfa)
+      if no file, reads from stdin
+      stream can be optionally gzipped.""",
+   [
+    Arg(
+        String,
+        "output path",
+        (short="-o", long="--output"),
+        "path to the svg file to store the figure in\n\tif empty, the figure is written to stdout",
+        "",
+    ),
+    Arg(
+        String,
+        "tree",
+        (short="-t", long="--tree"),
+        "path to a newick tree whose leaf order sets the order of the isolates\n\tif empty, isolates are sorted by name",
+        "",
+    ),
+    Arg(
+        String,
+        "isolates to draw",
+        (short="-s", long="--strains"),
+        "only draw the genomes of the given isolates.\n\tcomma seperated list, no spaces",
+        "",
+    ),
+    Arg(
+        Int,
+        "minimum block length",
+        (short="-ll", long="--minimum-length"),
+        "blocks below this length cutoff will not be drawn",
+        0,
+    ),
+    Arg(
+        Int,
+        "maximum block length",
+        (short="-lu", long="--maximum-length"),
+        "blocks above this length cutoff will not be drawn",
+        typemax(Int),
+    ),
+    Arg(
+        Int,
+        "minimum block depth",
+        (short="-dl", long="--mininum-depth"),
+        "blocks below this depth cutoff will not be drawn",
+        0,
+    ),
+    Arg(
+        Int,
+        "maximum block depth",
+        (short="-du", long="--maximum-depth"),
+        "blocks above this depth cutoff will not be drawn",
+        typemax(Int),
+    ),
+    Arg(
+        Int,
+        "width",
+        (short="-w", long="--width"),
+        "width, in pixels, of the longest genome",
+        1000,
+    ),
+   ],
+
+   function(args)
+       path = parse(Plot, args)
+       path = if (path === nothing || length(path) == 0)
+           nothing
+       elseif length(path) == 1
+           path
+       else
+           usage(Plot)
+           return 2
+       end
+
+       width = arg(Plot, "-w")
+       width > 0 || panic("width must be positive\n")
+
+       graph = load(path, Plot)
+
+       isolates = arg(Plot, "-s")
+       names = if length(isolates) > 0
+           names = split(isolates, ',')
+           for name in names
+               name ∈ keys(graph.sequence) || panic("isolate '$(name)' not found in pangraph\n")
+           end
+           names
+       else
+           collect(keys(graph.sequence))
+       end
+
+       order = sort(names)
+       tree  = arg(Plot, "-t")
+       if length(tree) > 0
+           !isfile(tree) && error("file '$(tree)' not found")
+           leaves = try
+               [leaf.name for leaf in Graphs.Align.leaves(Graphs.parse_newick(read(tree, String)))]
+           catch err
+               err isa ErrorException || rethrow()
+               panic("invalid tree '$(tree)': $(err.msg)\n")
+           end
+           # NOTE: the tree may hold more isolates than drawn
+           order = unique(leaf for leaf in leaves if leaf ∈ names)
+           for name in names
+               name ∈ order || panic("isolate '$(name)' not found in tree '$(tree)'\n")
+           end
+       end
+
+       cutoff = (
+           length = (min = arg(Plot, "-ll"), max = arg(Plot, "-lu")),
+           depth  = (min = arg(Plot, "-dl"), max = arg(Plot, "-du")),
+       )
+       keep = (b) -> (cutoff.length.min ≤ Blocks.length(b) ≤ cutoff.length.max
+                   && cutoff.depth.min  ≤ Blocks.depth(b)  ≤ cutoff.depth.max)
+
+       output = arg(Plot, "-o")
+       if length(output) > 0
+           Base.open(output, "w") do io
+               Graphs.Figures.write_svg(io, graph; order=order, keep=keep, width=width)
+           end
+       else
+           Graphs.Figures.write_svg(stdout, graph; order=order, keep=keep, width=width)
+       end
+
+       return 0
+   end
+)
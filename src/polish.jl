@@ -2,7 +2,7 @@ Polish = Command(
    "polish",
    "pangraph polish <options> [pangraph.json]",
    "realigns pancontigs of multiple sequence alignment graph",
-   """zero or one pangraph file (native json)
+   """zero or one pangraph file (json, binary or gfa)
       if no file, reads from stdin
       stream can be optionally gzipped.""",
    [
@@ -20,6 +20,13 @@ Polish = Command(
         "ensure case (upper/lower) is preserved after realignment",
         false,
     ),
+    Arg(
+        String,
+        "output format",
+        (short="-f", long="--format"),
+        "format of the output pangraph\n\trecognized options: [json, binary]",
+        "json",
+    ),
    ],
    function(args)
        path = parse(Polish, args)
@@ -32,6 +39,7 @@ Polish = Command(
        end
 
        graph = load(path, Polish)
+       fmt   = outputformat(Polish)
        if !Shell.havecommand("mafft")
            panic("external command mafft not found. please install before running polish step\n")
        end
@@ -40,9 +48,13 @@ Polish = Command(
        accept = function(blk)
            length(blk) ≤ arg(Polish, "-l") && Graphs.depth(blk) > 1
        end
+       history = [copy(graph.metadata)]
+       before  = attached(graph)
        Graphs.realign!(graph; accept=accept, case=case)
+       warnfeatures(before, graph)
+       provenance!(graph, Polish, path === nothing ? [] : path; history=history)
 
-       marshal(stdout, graph; fmt=:json)
+       marshal(stdout, graph; fmt=fmt)
        return 0
    end
 )
@@ -0,0 +1,79 @@
+module PresenceAbsence
+
+using Rematch
+
+import ..Graphs:
+    Graph,
+    count_isolates, depth, diversity
+
+export matrix, write_matrix
+
+"""
+    matrix(G::Graph)
+
+Return the number of copies of every block of graph `G` within each isolate.
+Blocks are sorted by decreasing depth, isolates by name.
+Return the blocks, the isolate names and the copy numbers, stored as one row per block and one column per isolate.
+"""
+function matrix(G::Graph)
+    names  = sort(collect(keys(G.sequence)))
+    counts = count_isolates(values(G.sequence))
+    blocks = sort(collect(keys(counts)); by=(b)->(-depth(b), b.uuid))
+    copies = [get(counts[b], name, 0) for b in blocks, name in names]
+
+    return blocks, names, copies
+end
+
+csv(field) = "\"$(replace(string(field), "\"" => "\"\""))\""
+
+function write_table(io::IO, blocks, names, copies, delim)
+    field = delim == ',' ? csv : string
+    write(io, join(field.(["block", "length", "depth", "diversity", names...]), delim), '\n')
+    for (i, b) in enumerate(blocks)
+        write(io, join(field.([b.uuid, length(b), depth(b), diversity(b), copies[i,:]...]), delim), '\n')
+    end
+end
+
+# layout of gene_presence_absence.csv as emitted by Roary
+function write_roary(io::IO, blocks, names, copies)
+    write(io, join(csv.([
+        "Gene", "Non-unique Gene name", "Annotation",
+        "No. isolates", "No. sequences", "Avg sequences per isolate",
+        "Genome Fragment", "Order within Fragment", "Accessory Fragment", "Accessory Order with Fragment",
+        "QC", "Min group size nuc", "Max group size nuc", "Avg group size nuc",
+        names...
+    ]), ','), '\n')
+
+    for (i, b) in enumerate(blocks)
+        isolates = count(>(0), copies[i,:])
+        lengths  = [length(b, node) for node in keys(b)]
+        loci     = [join(["$(name)#$(k)" for k in 1:copies[i,j]], '\t') for (j, name) in enumerate(names)]
+
+        write(io, join(csv.([
+            b.uuid, "", "pancontig",
+            isolates, depth(b), round(depth(b)/isolates; digits=2),
+            "", "", "", "",
+            "", minimum(lengths), maximum(lengths), round(sum(lengths)/length(lengths); digits=2),
+            loci...
+        ]), ','), '\n')
+    end
+end
+
+"""
+    write_matrix(io::IO, G::Graph; fmt=:tsv)
+
+Output the block presence/absence matrix of graph `G` to IO stream `io`.
+`fmt` can be either `:tsv` or `:csv`, in which case each row holds the length, depth and diversity of a block followed by its copy number within each isolate,
+or `:roary`, in which case the layout of Roary's `gene_presence_absence.csv` is used.
+"""
+function write_matrix(io::IO, G::Graph; fmt=:tsv)
+    blocks, names, copies = matrix(G)
+    @match fmt begin
+        :tsv   => write_table(io, blocks, names, copies, '\t')
+        :csv   => write_table(io, blocks, names, copies, ',')
+        :roary => write_roary(io, blocks, names, copies)
+        _      => error("$fmt not a recognized matrix format")
+    end
+end
+
+end
@@ -0,0 +1,262 @@
+module Regions
+
+import ..Graphs:
+    Graph, Block, Node, Path, Annotation,
+    alignment, positions!, swap!, depth, genomelength, copyindex
+
+export extract, liftover
+
+# ------------------------------------------------------------------------
+# coordinates
+
+"""
+    intervals(L, start, stop; circular=false)
+
+Return the interval `start:stop` of a genome of length `L` as a list of disjoint ranges within `1:L`.
+Circular genomes wrap around their origin, linear genomes are clamped to their ends.
+"""
+function intervals(L, start, stop; circular=false)
+    if !circular
+        start, stop = max(start, 1), min(stop, L)
+        return start ≤ stop ? [start:stop] : UnitRange{Int}[]
+    end
+
+    stop - start + 1 ≥ L && return [1:L]
+
+    start, stop = mod1(start, L), mod1(stop, L)
+    return start ≤ stop ? [start:stop] : [start:L, 1:stop]
+end
+
+"""
+    overlap(p::Path, i, region, L)
+
+Return the range of nucleotides of the `i`th node of path `p`, of total length `L`, counted along the path, that overlap any interval of `region`.
+Return `nothing` if the node does not overlap `region`.
+"""
+function overlap(p::Path, i, region, L)
+    len = length(p.node[i])
+    len > 0 || return nothing
+
+    start = p.position[i]
+    stop  = start + len - 1
+
+    # (genomic interval, index of its first nucleotide within the node)
+    pieces = stop ≤ L ? [(start:stop, 1)] : [(start:L, 1), (1:(stop-L), L-start+2)]
+
+    lo, hi = typemax(Int), typemin(Int)
+    for (piece, k) in pieces, I in region
+        J = intersect(piece, I)
+        isempty(J) && continue
+        lo = min(lo, first(J) - first(piece) + k)
+        hi = max(hi, last(J)  - first(piece) + k)
+    end
+
+    return lo ≤ hi ? (lo:hi) : nothing
+end
+
+"""
+    consensus(b::Block, node::Node, range)
+
+Return the interval of the consensus of block `b` aligned to the nucleotides `range` of `node`, counted along its path.
+Nucleotides inserted relative to the consensus are attributed to the flanking consensus positions.
+"""
+function consensus(b::Block, node::Node, range)
+    aln, nodes, ref = alignment(b)
+    row  = aln[:, findfirst((n) -> n === node, nodes)]
+    cols = [c for c in 1:length(row) if row[c] != UInt8('-')]
+    node.strand || reverse!(cols)
+
+    rank = cumsum(ref .!= UInt8('-'))
+    c₁, c₂ = minmax(cols[first(range)], cols[last(range)])
+
+    lo = ref[c₁] == UInt8('-') ? rank[c₁] + 1 : rank[c₁]
+    hi = rank[c₂]
+
+    lo = clamp(lo, 1, length(b))
+    hi = clamp(hi, lo, length(b))
+
+    return lo:hi
+end
+
+# ------------------------------------------------------------------------
+# extraction
+
+"""
+    extract(G::Graph, isolate, start, stop; flank=0)
+
+Return the sub-graph of `G` homologous to the interval `start:stop`, extended by `flank` nucleotides on both sides, of genome `isolate`.
+Coordinates are 1-based, inclusive and refer to the original rotation of `isolate`, i.e. including its circular offset.
+
+All blocks traversed by `isolate` within the interval are retained, along with their homologous nodes in all other isolates.
+Blocks only partially covered are sliced to the covered interval of their consensus.
+Each contiguous run of retained nodes within a genome becomes a linear path, named after the isolate if unique, or `isolate#k` for its `k`th run otherwise.
+Features attached to retained blocks are clipped to their slice.
+`G` is not modified.
+"""
+function extract(G::Graph, isolate, start, stop; flank=0)
+    isolate ∈ keys(G.sequence) || error("'$(isolate)' not a valid sequence identifier")
+    start ≤ stop || error("invalid region $(start)-$(stop)")
+
+    path   = G.sequence[isolate]
+    L      = genomelength(path)
+    region = intervals(L, start-flank, stop+flank; circular=path.circular)
+    isempty(region) && error("region $(start)-$(stop) lies outside of '$(isolate)' of length $(L)")
+
+    # consensus interval retained for each block
+    slices = Dict{Block,UnitRange{Int}}()
+    for (i, node) in enumerate(path.node)
+        covered = overlap(path, i, region, L)
+        covered === nothing && continue
+
+        slice = covered == 1:length(node) ? (1:length(node.block)) : consensus(node.block, node, covered)
+        if node.block ∈ keys(slices)
+            # NOTE: blocks traversed more than once keep the hull of all covered intervals
+            slice = min(first(slice), first(slices[node.block])):max(last(slice), last(slices[node.block]))
+        end
+        slices[node.block] = slice
+    end
+
+    blocks = Dict{Block,Block}()
+    for (b, slice) in slices
+        blocks[b] = Block(b, slice)
+        if slice == 1:length(b)
+            blocks[b].uuid = b.uuid
+        end
+    end
+
+    paths = Dict{String,Path}()
+    for name in sort(collect(keys(G.sequence)))
+        p = G.sequence[name]
+        n = length(p.node)
+
+        # NOTE: runs of circular genomes must not be split at the origin
+        origin = 1
+        if p.circular && any(node.block ∉ keys(blocks) for node in p.node)
+            while p.node[origin].block ∈ keys(blocks)
+                origin += 1
+            end
+        end
+
+        runs  = Array{Node{Block},1}[]
+        inrun = false
+        for j in 0:(n-1)
+            node = p.node[mod1(origin+j, n)]
+            if node.block ∉ keys(blocks)
+                inrun = false
+                continue
+            end
+
+            b   = blocks[node.block]
+            new = Node{Block}(b, node.strand)
+            swap!(b, node, new)
+
+            inrun || push!(runs, Node{Block}[])
+            inrun = true
+
+            if length(new) == 0
+                pop!(b, new)
+            else
+                push!(runs[end], new)
+            end
+        end
+        filter!((run) -> length(run) > 0, runs)
+
+        for (k, run) in enumerate(runs)
+            id = length(runs) == 1 ? name : "$(name)#$(k)"
+            paths[id] = Path(id, run, nothing, false, Int[])
+            positions!(paths[id])
+        end
+    end
+
+    annotation = Dict{String,Array{Annotation,1}}()
+    for (b, slice) in slices
+        new = blocks[b]
+        depth(new) > 0 || continue
+
+        features = [
+            Annotation(a, max(a.start, first(slice))-first(slice)+1, min(a.stop, last(slice))-first(slice)+1, a.strand)
+            for a in get(G.annotation, b.uuid, Annotation[]) if a.start ≤ last(slice) && a.stop ≥ first(slice)
+        ]
+        isempty(features) || (annotation[new.uuid] = features)
+    end
+
+    return Graph(
+        Dict(b.uuid => b for b in values(blocks) if depth(b) > 0),
+        paths,
+        Dict{String,Any}(),
+        annotation,
+    )
+end
+
+# ------------------------------------------------------------------------
+# liftover
+
+"""
+    columns(b::Block)
+
+Return the alignment column, in the orientation of the consensus, of every nucleotide of every node of block `b`.
+"""
+function columns(b::Block)
+    aln, nodes, _ = alignment(b)
+    gap = UInt8('-')
+    return Dict(node => [c for c in 1:size(aln,1) if aln[c,j] != gap] for (j, node) in enumerate(nodes))
+end
+
+"""
+    liftover(G::Graph, from, pos, to; cache=Dict{Block,Any}())
+
+Return the positions homologous to position `pos` of isolate `from` within isolate `to` of graph `G`.
+Positions are 1-based and refer to the original rotation of circular genomes.
+The position is mapped onto the alignment column of its block and back out through every copy of the block found in `to`.
+
+Return an array with one named tuple per copy, holding the `block` identifier, the 1-based `copy` number of the block within `to` (counted along its path),
+the homologous `position`, or `nothing` if deleted in that copy, and whether the copy is on the same `strand` as the queried position.
+The array is empty if `to` does not contain the block.
+`cache` stores the alignment columns of blocks across calls.
+"""
+function liftover(G::Graph, from, pos, to; cache=Dict{Block,Any}())
+    from ∈ keys(G.sequence) || error("'$(from)' not a valid sequence identifier")
+    to   ∈ keys(G.sequence) || error("'$(to)' not a valid sequence identifier")
+
+    path = G.sequence[from]
+    L    = genomelength(path)
+    1 ≤ pos ≤ L || error("position $(pos) lies outside of '$(from)' of length $(L)")
+
+    # node of the source genome, along with the index of the position within it
+    i = findfirst(1:length(path.node)) do i
+        len = length(path.node[i])
+        len > 0 && mod(pos - path.position[i], L) < len
+    end
+    node = path.node[i]
+    k    = mod(pos - path.position[i], L) + 1
+
+    cols = get!(() -> columns(node.block), cache, node.block)
+    col  = node.strand ? cols[node][k] : cols[node][end-k+1]
+
+    target = G.sequence[to]
+    M      = genomelength(target)
+    copies = copyindex(target)
+    result = []
+    for (j, n) in enumerate(target.node)
+        n.block === node.block || continue
+
+        r = searchsortedlast(cols[n], col)
+        position = if r == 0 || cols[n][r] != col
+            nothing
+        else
+            x = n.strand ? r : length(cols[n]) - r + 1
+            mod1(target.position[j] + x - 1, M)
+        end
+
+        push!(result, (
+            block    = n.block.uuid,
+            copy     = copies[j],
+            position = position,
+            strand   = n.strand == node.strand,
+        ))
+    end
+
+    return result
+end
+
+end
@@ -0,0 +1,286 @@
+module Schema
+
+export migrate!, validate, report
+
+"""
+    CURRENT
+
+Version of the json schema emitted by `marshal_json`.
+Version 1 corresponds to unversioned files written before the schema was versioned.
+"""
+const CURRENT = 3
+
+# ------------------------------------------------------------------------
+# migrations
+
+# NOTE: each migration upgrades a parsed json file by exactly one version
+#       migrations must not assume the input is well-formed; validation happens afterwards
+const MIGRATIONS = Dict{Int,Function}(
+    # unversioned -> 2: positions are optional and graph-level metadata is introduced
+    1 => function(graph)
+        for path in get(graph, "paths", [])
+            if path isa AbstractDict && !haskey(path, "position")
+                path["position"] = []
+            end
+        end
+        graph["metadata"] = Dict{String,Any}()
+        graph["version"]  = 2
+    end,
+    # 2 -> 3: blocks carry annotated features
+    2 => function(graph)
+        for blk in get(graph, "blocks", [])
+            if blk isa AbstractDict && !haskey(blk, "annotations")
+                blk["annotations"] = []
+            end
+        end
+        graph["version"] = 3
+    end,
+)
+
+"""
+    migrate!(graph)
+
+Upgrade the parsed json representation of a pangraph, `graph`, to the current schema version.
+Files without a `version` field are assumed to be version 1.
+Each migration is applied in turn.
+"""
+function migrate!(graph)
+    graph isa AbstractDict || error("malformed pangraph: expected a json object at the top level")
+
+    version = get(graph, "version", 1)
+    (version isa Integer && !(version isa Bool)) || error("malformed pangraph: field 'version' is not an integer")
+    version ≤ CURRENT || error("pangraph schema version $(version) is newer than supported version $(CURRENT). please upgrade pangraph")
+
+    while version < CURRENT
+        MIGRATIONS[version](graph)
+        version = graph["version"]
+    end
+
+    return graph
+end
+
+# ------------------------------------------------------------------------
+# validation
+
+const KIND = (
+    string  = ("a string",          (x) -> x isa AbstractString),
+    integer = ("an integer",        (x) -> x isa Integer && !(x isa Bool)),
+    boolean = ("a boolean",         (x) -> x isa Bool),
+    array   = ("an array",          (x) -> x isa AbstractVector),
+    object  = ("an object",         (x) -> x isa AbstractDict),
+    offset  = ("an integer or null",(x) -> x === nothing || (x isa Integer && !(x isa Bool))),
+)
+
+jsontype(x) =
+    if x === nothing
+        "null"
+    elseif x isa Bool
+        "a boolean"
+    elseif x isa Number
+        "a number"
+    elseif x isa AbstractString
+        "a string"
+    elseif x isa AbstractVector
+        "an array"
+    elseif x isa AbstractDict
+        "an object"
+    else
+        string(typeof(x))
+    end
+
+# check that value `x` found at `loc` is of `kind`, recording a violation otherwise
+function check!(violations, x, kind, loc)
+    desc, ok = kind
+    ok(x) && return true
+    push!(violations, "$(loc): expected $(desc), found $(jsontype(x))")
+    return false
+end
+
+# check that object `obj` found at `loc` has field `key` of `kind`, recording a violation otherwise
+function field!(violations, obj, key, kind, loc)
+    if !haskey(obj, key)
+        push!(violations, "$(loc): missing field '$(key)'")
+        return false
+    end
+    return check!(violations, obj[key], kind, "$(loc).$(key)")
+end
+
+function node!(violations, node, loc; id=false)
+    check!(violations, node, KIND.object, loc) || return nothing
+    ok = true
+    id && (ok &= field!(violations, node, "id", KIND.string, loc))
+    ok &= field!(violations, node, "name",   KIND.string,  loc)
+    ok &= field!(violations, node, "number", KIND.integer, loc)
+    ok &= field!(violations, node, "strand", KIND.boolean, loc)
+    return ok ? (node["name"], node["number"], node["strand"]) : nothing
+end
+
+# feature attached to a block, see `Graphs.Annotation`
+function annotation!(violations, annotation, loc)
+    check!(violations, annotation, KIND.object, loc) || return
+    for key in ("id", "isolate", "source", "type", "phase", "attributes")
+        field!(violations, annotation, key, KIND.string, loc)
+    end
+    for key in ("start", "stop", "part", "parts", "length")
+        field!(violations, annotation, key, KIND.integer, loc)
+    end
+    if field!(violations, annotation, "strand", KIND.string, loc) && annotation["strand"] ∉ ("+", "-", ".")
+        push!(violations, "$(loc).strand: expected one of '+', '-' or '.', found '$(annotation["strand"])'")
+    end
+end
+
+# alleles of a single node: snps as (locus, nucleotide), insertions as ((locus, offset), sequence), deletions as (locus, length)
+function allele!(violations, allele, field, loc)
+    check!(violations, allele, KIND.array, loc) || return
+    if length(allele) != 2
+        push!(violations, "$(loc): expected a pair, found an array of length $(length(allele))")
+        return
+    end
+
+    if field == "insert"
+        key = allele[1]
+        if check!(violations, key, KIND.array, "$(loc)[1]")
+            if length(key) != 2
+                push!(violations, "$(loc)[1]: expected a pair, found an array of length $(length(key))")
+            else
+                check!(violations, key[1], KIND.integer, "$(loc)[1][1]")
+                check!(violations, key[2], KIND.integer, "$(loc)[1][2]")
+            end
+        end
+    else
+        check!(violations, allele[1], KIND.integer, "$(loc)[1]")
+    end
+
+    if field == "mutate"
+        if check!(violations, allele[2], KIND.string, "$(loc)[2]") && length(allele[2]) != 1
+            push!(violations, "$(loc)[2]: expected a single nucleotide, found '$(allele[2])'")
+        end
+    elseif field == "insert"
+        check!(violations, allele[2], KIND.string, "$(loc)[2]")
+    else
+        check!(violations, allele[2], KIND.integer, "$(loc)[2]")
+    end
+end
+
+"""
+    validate(graph)
+
+Check the parsed json representation of a pangraph, `graph`, against the current schema version.
+Cross references between paths and blocks are checked as well.
+Return an array of human-readable schema violations, empty if `graph` is well-formed.
+"""
+function validate(graph)
+    violations = String[]
+
+    check!(violations, graph, KIND.object, "pangraph") || return violations
+    field!(violations, graph, "version",  KIND.integer, "pangraph")
+    field!(violations, graph, "metadata", KIND.object,  "pangraph")
+    haspaths  = field!(violations, graph, "paths",  KIND.array, "pangraph")
+    hasblocks = field!(violations, graph, "blocks", KIND.array, "pangraph")
+
+    # block id -> variant field -> set of nodes
+    nodes = Dict{String,Dict{String,Set{Tuple{String,Int,Bool}}}}()
+    index = Dict{String,Int}()
+    if hasblocks
+        for (i, blk) in enumerate(graph["blocks"])
+            loc = "blocks[$(i)]"
+            check!(violations, blk, KIND.object, loc) || continue
+
+            if field!(violations, blk, "id", KIND.string, loc)
+                blk["id"] ∈ keys(index) && push!(violations, "$(loc).id: duplicate block '$(blk["id"])', first found at blocks[$(index[blk["id"]])]")
+                index[blk["id"]] = i
+            end
+            field!(violations, blk, "sequence", KIND.string, loc)
+            if field!(violations, blk, "gaps", KIND.object, loc)
+                for (k, v) in blk["gaps"]
+                    tryparse(Int, k) === nothing && push!(violations, "$(loc).gaps: key '$(k)' is not an integer")
+                    check!(violations, v, KIND.integer, "$(loc).gaps.$(k)")
+                end
+            end
+
+            fields = Dict{String,Set{Tuple{String,Int,Bool}}}()
+            for name in ("mutate", "insert", "delete")
+                fields[name] = Set{Tuple{String,Int,Bool}}()
+                field!(violations, blk, name, KIND.array, loc) || continue
+                for (j, entry) in enumerate(blk[name])
+                    at = "$(loc).$(name)[$(j)]"
+                    check!(violations, entry, KIND.array, at) || continue
+                    if length(entry) != 2
+                        push!(violations, "$(at): expected a (node, alleles) pair, found an array of length $(length(entry))")
+                        continue
+                    end
+
+                    key = node!(violations, entry[1], "$(at)[1]")
+                    key === nothing || push!(fields[name], key)
+
+                    check!(violations, entry[2], KIND.array, "$(at)[2]") || continue
+                    for (k, allele) in enumerate(entry[2])
+                        allele!(violations, allele, name, "$(at)[2][$(k)]")
+                    end
+                end
+            end
+
+            if field!(violations, blk, "annotations", KIND.array, loc)
+                for (j, annotation) in enumerate(blk["annotations"])
+                    annotation!(violations, annotation, "$(loc).annotations[$(j)]")
+                end
+            end
+
+            haskey(blk, "id") && blk["id"] isa AbstractString && (nodes[blk["id"]] = fields)
+        end
+    end
+
+    if haspaths
+        names = Dict{String,Int}()
+        for (i, path) in enumerate(graph["paths"])
+            loc = "paths[$(i)]"
+            check!(violations, path, KIND.object, loc) || continue
+
+            if field!(violations, path, "name", KIND.string, loc)
+                path["name"] ∈ keys(names) && push!(violations, "$(loc).name: duplicate path '$(path["name"])', first found at paths[$(names[path["name"]])]")
+                names[path["name"]] = i
+            end
+            field!(violations, path, "offset",   KIND.offset,  loc)
+            field!(violations, path, "circular", KIND.boolean, loc)
+            if field!(violations, path, "position", KIND.array, loc)
+                for (j, x) in enumerate(path["position"])
+                    check!(violations, x, KIND.integer, "$(loc).position[$(j)]")
+                end
+            end
+
+            field!(violations, path, "blocks", KIND.array, loc) || continue
+            for (j, node) in enumerate(path["blocks"])
+                at  = "$(loc).blocks[$(j)]"
+                key = node!(violations, node, at; id=true)
+                (key === nothing || !hasblocks) && continue
+
+                id = node["id"]
+                if id ∉ keys(nodes)
+                    push!(violations, "$(at): unknown block '$(id)'")
+                    continue
+                end
+
+                for name in ("mutate", "insert", "delete")
+                    key ∈ nodes[id][name] || push!(violations, "blocks[$(index[id])].$(name): no entry for node $(key) of $(at)")
+                end
+            end
+        end
+    end
+
+    return violations
+end
+
+"""
+    report(violations; limit=50)
+
+Format the schema `violations` returned by `validate` into an error message.
+At most `limit` violations are listed.
+"""
+function report(violations; limit=50)
+    lines = ["malformed pangraph: $(length(violations)) schema violation(s)"]
+    append!(lines, ["\t" * v for v in violations[1:min(limit, end)]])
+    length(violations) > limit && push!(lines, "\t... and $(length(violations) - limit) more")
+    return join(lines, '\n')
+end
+
+end
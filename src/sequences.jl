@@ -0,0 +1,75 @@
+Sequences = Command(
+   "sequences",
+   "pangraph sequences <options> [arguments]",
+   "outputs the genomes encoded by a multiple sequence alignment graph as fasta",
+   """zero or one pangraph file (json, binary or gfa)
+      if no file, reads from stdin
+      stream can be optionally gzipped.""",
+   [
+    Arg(
+        String,
+        "isolates to output",
+        (short="-s", long="--strains"),
+        "only output the genomes of the given isolates.\n\tcomma seperated list, no spaces",
+        "",
+    ),
+    Arg(
+        Bool,
+        "ignore circular offset",
+        (short="-n", long="--no-offset"),
+        "do not restore the original rotation of circular genomes.\n\tgenomes start at the first block of their path instead",
+        false,
+    ),
+    Arg(
+        String,
+        "output path",
+        (short="-o", long="--output-path"),
+        "path to directory where each genome will be stored in its own fasta file\n\tif empty, all genomes are written to stdout",
+        "",
+    ),
+   ],
+
+   function(args)
+       path = parse(Sequences, args)
+       path = if (path === nothing || length(path) == 0)
+           nothing
+       elseif length(path) == 1
+           path
+       else
+           usage(Sequences)
+           return 2
+       end
+
+       graph = load(path, Sequences)
+
+       isolates = arg(Sequences, "-s")
+       names = if length(isolates) > 0
+           names = split(isolates, ',')
+           for name in names
+               name ∈ keys(graph.sequence) || panic("isolate '$(name)' not found in pangraph\n")
+           end
+           names
+       else
+           sort(collect(keys(graph.sequence)))
+       end
+
+       shift  = !arg(Sequences, "-n")
+       output = arg(Sequences, "-o")
+       if length(output) > 0
+           isdir(output) || mkpath(output)
+       end
+
+       for name in names
+           seq = sequence(graph.sequence[name]; shift=shift)
+           if length(output) > 0
+               Base.open("$(output)/$(filename(name)).fa", "w") do io
+                   write_fasta(io, name, seq)
+               end
+           else
+               write_fasta(stdout, name, seq)
+           end
+       end
+
+       return 0
+   end
+)
@@ -0,0 +1,44 @@
+Serve = Command(
+   "serve",
+   "pangraph serve <options> [arguments]",
+   "keeps a multiple sequence alignment graph in memory and answers queries through a local HTTP/JSON interface",
+   """zero or one pangraph file (json, binary or gfa)
+      if no file, reads from stdin
+      stream can be optionally gzipped.""",
+   [
+    Arg(
+        Int,
+        "port",
+        (short="-p", long="--port"),
+        "port to listen on",
+        8080,
+    ),
+    Arg(
+        String,
+        "host",
+        (short="-H", long="--host"),
+        "ip address to listen on\n\tdefaults to local connections only",
+        "127.0.0.1",
+    ),
+   ],
+
+   function(args)
+       path = parse(Serve, args)
+       path = if (path === nothing || length(path) == 0)
+           nothing
+       elseif length(path) == 1
+           path
+       else
+           usage(Serve)
+           return 2
+       end
+
+       port = arg(Serve, "-p")
+       0 < port < 65536 || panic("port must lie between 1 and 65535\n")
+
+       graph = load(path, Serve)
+       Graphs.Server.serve(graph; host=arg(Serve, "-H"), port=port)
+
+       return 0
+   end
+)
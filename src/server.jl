@@ -0,0 +1,314 @@
+module Server
+
+using Sockets
+import JSON
+
+import ..Graphs
+import ..Graphs: Graph, Block, Node, depth, diversity, alignment, genomelength
+
+export serve, respond
+
+# ------------------------------------------------------------------------
+# http
+
+const REASON = Dict(
+    200 => "OK",
+    400 => "Bad Request",
+    404 => "Not Found",
+    405 => "Method Not Allowed",
+    500 => "Internal Server Error",
+)
+
+struct Failure <: Exception
+    status  :: Int
+    message :: String
+end
+
+notfound(msg)   = throw(Failure(404, msg))
+badrequest(msg) = throw(Failure(400, msg))
+
+# decode a percent-encoded component of an url
+function unescape(s)
+    s   = replace(s, '+' => ' ')
+    out = UInt8[]
+    i   = 1
+    while i ≤ ncodeunits(s)
+        c = codeunit(s, i)
+        if c == UInt8('%') && i+2 ≤ ncodeunits(s)
+            x = tryparse(UInt8, s[i+1:i+2]; base=16)
+            x === nothing && badrequest("malformed percent-encoding in '$(s)'")
+            push!(out, x)
+            i += 3
+        else
+            push!(out, c)
+            i += 1
+        end
+    end
+    return String(out)
+end
+
+# split a request target into its decoded path components and query parameters
+function route(target)
+    path, query = occursin('?', target) ? split(target, '?'; limit=2) : (target, "")
+    parts  = [unescape(p) for p in split(path, '/') if !isempty(p)]
+    params = Dict{String,String}()
+    for pair in split(query, '&')
+        isempty(pair) && continue
+        key, value = occursin('=', pair) ? split(pair, '='; limit=2) : (pair, "")
+        params[unescape(key)] = unescape(value)
+    end
+    return parts, params
+end
+
+function parameter(params, key, T=String; default=nothing)
+    if !haskey(params, key)
+        default === nothing && badrequest("missing query parameter '$(key)'")
+        return default
+    end
+    T === String && return params[key]
+    x = tryparse(T, params[key])
+    x === nothing && badrequest("query parameter '$(key)' is not of type $(T)")
+    return x
+end
+
+# ------------------------------------------------------------------------
+# endpoints
+
+"""
+    struct State
+        graph :: Graph
+        locus :: Dict
+        cache :: Dict{Block,Any}
+        lock  :: ReentrantLock
+    end
+
+Data retained in memory by the server between requests: the loaded `graph`, the location of each node within its genome,
+and the alignment columns of blocks queried so far.
+"""
+struct State
+    graph :: Graph
+    locus :: Dict
+    cache :: Dict{Block,Any}
+    lock  :: ReentrantLock
+end
+
+State(G::Graph) = State(G, Graphs.Alignments.loci(G), Dict{Block,Any}(), ReentrantLock())
+
+function block(S::State, id)
+    id ∈ keys(S.graph.block) || notfound("block '$(id)' not found")
+    return S.graph.block[id]
+end
+
+function isolate(S::State, name)
+    name ∈ keys(S.graph.sequence) || notfound("isolate '$(name)' not found")
+    return S.graph.sequence[name]
+end
+
+function brief(b::Block)
+    return (
+        id        = b.uuid,
+        length    = length(b),
+        depth     = depth(b),
+        diversity = diversity(b),
+    )
+end
+
+function stats(S::State, b::Block)
+    copies = Dict{String,Int}()
+    for node in keys(b.mutate)
+        name = S.locus[node].isolate
+        copies[name] = get(copies, name, 0) + 1
+    end
+    return merge(brief(b), (
+        sequence = String(copy(b.sequence)),
+        gaps     = sum(values(b.gaps); init=0),
+        snps     = sum(length(m) for m in values(b.mutate); init=0),
+        copies   = copies,
+    ))
+end
+
+function rows(S::State, b::Block)
+    aln, nodes, ref = alignment(b)
+    order = sortperm([(S.locus[n].isolate, S.locus[n].copy) for n in nodes])
+    return (
+        id        = b.uuid,
+        consensus = String(ref),
+        rows      = [
+            let n = nodes[j], l = S.locus[n]
+                (
+                    isolate  = l.isolate,
+                    copy     = l.copy,
+                    strand   = n.strand ? "+" : "-",
+                    start    = l.start,
+                    stop     = mod1(l.start + l.length - 1, l.total),
+                    sequence = String(aln[:,j]),
+                )
+            end for j in order
+        ],
+    )
+end
+
+function path(S::State, name)
+    p = isolate(S, name)
+    return (
+        name     = p.name,
+        circular = p.circular,
+        offset   = p.offset,
+        length   = genomelength(p),
+        blocks   = [
+            (
+                id       = node.block.uuid,
+                strand   = node.strand ? "+" : "-",
+                position = p.position[i],
+                length   = length(node),
+            ) for (i, node) in enumerate(p.node)
+        ],
+    )
+end
+
+function liftover(S::State, params)
+    from = parameter(params, "from")
+    pos  = parameter(params, "position", Int)
+    isolate(S, from)
+
+    targets = haskey(params, "to") ? split(params["to"], ',') : sort(collect(keys(S.graph.sequence)))
+    foreach((name) -> isolate(S, name), targets)
+
+    L = genomelength(S.graph.sequence[from])
+    1 ≤ pos ≤ L || badrequest("position $(pos) lies outside of '$(from)' of length $(L)")
+
+    # NOTE: the column cache is shared across concurrent requests
+    hits = lock(S.lock) do
+        Dict(to => Graphs.liftover(S.graph, from, pos, to; cache=S.cache) for to in targets)
+    end
+    return (source = from, position = pos, targets = hits)
+end
+
+function region(S::State, params)
+    name  = parameter(params, "isolate")
+    start = parameter(params, "start", Int)
+    stop  = parameter(params, "end", Int)
+    flank = parameter(params, "flank", Int; default=0)
+    isolate(S, name)
+
+    start ≤ stop || badrequest("invalid region $(start)-$(stop)")
+    flank ≥ 0    || badrequest("flank length must be non-negative")
+
+    G  = Graphs.Regions.extract(S.graph, name, start, stop; flank=flank)
+    io = IOBuffer()
+    Graphs.marshal(io, G; fmt=:json)
+    # NOTE: already serialized, see respond
+    return String(take!(io))
+end
+
+"""
+    respond(S::State, method, target)
+
+Compute the response to the HTTP request `method target` against the graph held by server state `S`.
+Return the status code along with the body, as a json string.
+
+Recognized endpoints, all answering `GET` requests, are
+  - `/isolates`: name, length, topology and number of blocks of every isolate.
+  - `/blocks`: identifier, length, depth and diversity of every block.
+  - `/blocks/<id>`: statistics of one block, including its consensus and copy number within each isolate.
+  - `/blocks/<id>/alignment`: multiple sequence alignment of one block, in the orientation of its consensus.
+  - `/paths/<isolate>`: the blocks traversed by one isolate, with strand and position.
+  - `/liftover?from=<isolate>&position=<pos>[&to=<isolates>]`: homologous positions within other isolates, see `Graphs.liftover`.
+  - `/region?isolate=<isolate>&start=<start>&end=<end>[&flank=<n>]`: the sub-graph homologous to a region, see `Graphs.Regions.extract`.
+"""
+function respond(S::State, method, target)
+    try
+        method == "GET" || throw(Failure(405, "method '$(method)' not allowed"))
+
+        parts, params = route(target)
+        body = if parts == ["isolates"]
+            [
+                (
+                    name     = name,
+                    length   = genomelength(p),
+                    circular = p.circular,
+                    blocks   = length(p.node),
+                ) for (name, p) in sort(collect(S.graph.sequence); by=first)
+            ]
+        elseif parts == ["blocks"]
+            [brief(b) for b in sort(collect(values(S.graph.block)); by=(b)->b.uuid)]
+        elseif length(parts) == 2 && parts[1] == "blocks"
+            stats(S, block(S, parts[2]))
+        elseif length(parts) == 3 && parts[1] == "blocks" && parts[3] == "alignment"
+            rows(S, block(S, parts[2]))
+        elseif length(parts) == 2 && parts[1] == "paths"
+            path(S, parts[2])
+        elseif parts == ["liftover"]
+            liftover(S, params)
+        elseif parts == ["region"]
+            return 200, region(S, params)
+        else
+            notfound("unknown endpoint '$(target)'")
+        end
+
+        return 200, JSON.json(body)
+    catch err
+        err isa Failure && return err.status, JSON.json((error=err.message,))
+        @error "failed to serve '$(target)'" exception=(err, catch_backtrace())
+        return 500, JSON.json((error=sprint(showerror, err),))
+    end
+end
+
+# ------------------------------------------------------------------------
+# connections
+
+function handle(S::State, sock)
+    try
+        request = readline(sock)
+        # NOTE: headers and body are ignored, all endpoints are read-only
+        while !eof(sock) && !isempty(strip(readline(sock)))
+        end
+
+        field = split(request, ' ')
+        status, body = if length(field) == 3 && startswith(field[3], "HTTP/")
+            respond(S, field[1], field[2])
+        else
+            400, JSON.json((error="malformed request line '$(request)'",))
+        end
+
+        write(sock,
+            "HTTP/1.1 $(status) $(REASON[status])\r\n",
+            "Content-Type: application/json\r\n",
+            "Content-Length: $(sizeof(body))\r\n",
+            "Access-Control-Allow-Origin: *\r\n",
+            "Connection: close\r\n",
+            "\r\n",
+            body,
+        )
+    catch err
+        err isa Base.IOError || @error "connection failed" exception=(err, catch_backtrace())
+    finally
+        close(sock)
+    end
+end
+
+"""
+    serve(G::Graph; host="127.0.0.1", port=8080)
+
+Answer HTTP requests for the content of graph `G` on `host:port` until interrupted, see `respond` for the available endpoints.
+The server only accepts local connections unless `host` is set to a public address.
+Each connection is handled by its own task and serves a single request.
+"""
+function serve(G::Graph; host="127.0.0.1", port=8080)
+    address = tryparse(IPAddr, host)
+    address === nothing && error("'$(host)' is not a valid ip address")
+
+    S      = State(G)
+    server = listen(address, port)
+    @info "serving pangraph on http://$(host):$(port)"
+    try
+        while true
+            sock = accept(server)
+            @async handle(S, sock)
+        end
+    finally
+        close(server)
+    end
+end
+
+end
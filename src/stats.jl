@@ -0,0 +1,76 @@
+Stats = Command(
+   "stats",
+   "pangraph stats <options> [arguments]",
+   "computes summary statistics of a multiple sequence alignment graph",
+   """zero or one pangraph file (json, binary or gfa)
+      if no file, reads from stdin
+      stream can be optionally gzipped.""",
+   [
+    Arg(
+        Float64,
+        "core threshold",
+        (short="-c", long="--core-threshold"),
+        "minimum fraction of isolates a block must be found in to be considered core",
+        1.0,
+    ),
+    Arg(
+        Float64,
+        "cloud threshold",
+        (short="-C", long="--cloud-threshold"),
+        "maximum fraction of isolates a block must be found in to be considered cloud\n\tblocks in between are considered shell",
+        0.15,
+    ),
+    Arg(
+        String,
+        "output format",
+        (short="-f", long="--format"),
+        "format of the statistics\n\trecognized options: [json, tsv]",
+        "json",
+    ),
+    Arg(
+        String,
+        "output path",
+        (short="-o", long="--output"),
+        "path to store the statistics\n\tif empty, the statistics are written to stdout",
+        "",
+    ),
+   ],
+
+   function(args)
+       path = parse(Stats, args)
+       path = if (path === nothing || length(path) == 0)
+           nothing
+       elseif length(path) == 1
+           path
+       else
+           usage(Stats)
+           return 2
+       end
+
+       fmt = @match arg(Stats, "-f") begin
+           "json" => :json
+           "tsv"  => :tsv
+           _      => begin
+               usage(Stats)
+               exit(1)
+           end
+       end
+
+       core, cloud = arg(Stats, "-c"), arg(Stats, "-C")
+       0 ≤ cloud ≤ core ≤ 1 || panic("thresholds must satisfy 0 ≤ cloud threshold ≤ core threshold ≤ 1\n")
+
+       graph = load(path, Stats)
+       stats = Graphs.Summary.summarize(graph; core=core, cloud=cloud)
+
+       output = arg(Stats, "-o")
+       if length(output) > 0
+           Base.open(output, "w") do io
+               Graphs.Summary.write_summary(io, stats; fmt=fmt)
+           end
+       else
+           Graphs.Summary.write_summary(stdout, stats; fmt=fmt)
+       end
+
+       return 0
+   end
+)
@@ -0,0 +1,119 @@
+module Summary
+
+import JSON
+import ..Graphs:
+    Graph, Block,
+    count_isolates, depth, genomelength
+
+export summarize, write_summary
+
+"""
+    nx(lengths, x)
+
+Return the length `N` such that blocks at least as long as `N` cover at least a fraction `x` of the total length of `lengths`,
+along with the number `L` of such blocks. `nx(lengths, 0.5)` returns the N50 and L50.
+"""
+function nx(lengths, x)
+    isempty(lengths) && return (N=0, L=0)
+    sorted = sort(lengths; rev=true)
+    total  = cumsum(sorted)
+    i = findfirst((t) -> t ≥ x*total[end], total)
+    return (N=sorted[i], L=i)
+end
+
+"""
+    summarize(G::Graph; core=1.0, cloud=0.15)
+
+Return summary statistics of graph `G` as a named tuple:
+  - `blocks`: number of blocks, total (pangenome) and mean consensus length, N50 and L50 of consensus lengths.
+  - `genomes`: number of isolates, total genome length and the compression ratio, i.e. total genome length over pangenome length.
+  - `partition`: number and total consensus length of core, shell and cloud blocks.
+    Blocks are partitioned by the fraction of isolates they are found in: core blocks in at least `core`, cloud blocks in at most `cloud`, shell blocks in between.
+  - `paralogs`: number and total consensus length of blocks found more than once within at least one isolate.
+  - `isolates`: for each isolate, its length, number of blocks, private sequence length (found in no other isolate) and length within paralogous blocks.
+"""
+function summarize(G::Graph; core=1.0, cloud=0.15)
+    0 ≤ cloud ≤ core ≤ 1 || error("partition thresholds must satisfy 0 ≤ cloud ≤ core ≤ 1")
+
+    counts  = count_isolates(values(G.sequence))
+    N       = length(G.sequence)
+    lengths = [length(b) for b in values(G.block)]
+    total   = sum(lengths; init=0)
+    n50     = nx(lengths, 0.5)
+
+    isolates = Dict(b => length(keys(c)) for (b, c) in counts)
+    paralog  = Dict(b => any(n > 1 for n in values(c)) for (b, c) in counts)
+    class    = (b) -> let f = get(isolates, b, 0)/max(N, 1)
+        f ≥ core ? :core : f ≤ cloud ? :cloud : :shell
+    end
+
+    partition = Dict(k => (blocks=0, length=0) for k in (:core, :shell, :cloud))
+    for b in values(G.block)
+        k = class(b)
+        partition[k] = (blocks=partition[k].blocks+1, length=partition[k].length+length(b))
+    end
+
+    genomes = [
+        let p = G.sequence[name]
+            (
+                name     = name,
+                length   = genomelength(p),
+                blocks   = length(p.node),
+                private  = sum((length(node) for node in p.node if get(isolates, node.block, 0) == 1); init=0),
+                paralogs = sum((length(node) for node in p.node if get(paralog, node.block, false)); init=0),
+            )
+        end for name in sort(collect(keys(G.sequence)))
+    ]
+    span = sum(g.length for g in genomes; init=0)
+
+    return (
+        blocks = (
+            number = length(G.block),
+            length = total,
+            mean   = isempty(lengths) ? 0.0 : total/length(lengths),
+            N50    = n50.N,
+            L50    = n50.L,
+        ),
+        genomes = (
+            number      = N,
+            length      = span,
+            compression = total > 0 ? span/total : 0.0,
+        ),
+        partition = (
+            core  = partition[:core],
+            shell = partition[:shell],
+            cloud = partition[:cloud],
+        ),
+        paralogs = (
+            blocks = count(values(paralog)),
+            length = sum((length(b) for (b, dup) in paralog if dup); init=0),
+        ),
+        isolates = genomes,
+    )
+end
+
+# flatten nested named tuples into (dotted key, value) pairs; isolates are keyed by name
+flatten(x, prefix) = [prefix => x]
+flatten(x::NamedTuple, prefix) = vcat([flatten(v, isempty(prefix) ? String(k) : "$(prefix).$(k)") for (k, v) in pairs(x)]...)
+flatten(x::AbstractVector, prefix) = vcat([flatten(Base.structdiff(v, (name=nothing,)), "$(prefix).$(v.name)") for v in x]...)
+
+"""
+    write_summary(io::IO, stats; fmt=:json)
+
+Output the summary statistics `stats`, as returned by `summarize`, to IO stream `io`.
+`fmt` can be either `:json` or `:tsv`, in which case nested fields are flattened to one `statistic value` row each, with keys joined by dots.
+"""
+function write_summary(io::IO, stats; fmt=:json)
+    if fmt == :json
+        JSON.print(io, stats, 2)
+    elseif fmt == :tsv
+        write(io, "statistic\tvalue\n")
+        for (key, value) in flatten(stats, "")
+            write(io, key, '\t', string(value), '\n')
+        end
+    else
+        error("$fmt not a recognized statistics format")
+    end
+end
+
+end
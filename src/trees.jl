@@ -0,0 +1,106 @@
+Trees = Command(
+   "trees",
+   "pangraph trees <options> [arguments]",
+   "compares the phylogeny of every block to the core genome phylogeny to flag candidate horizontal transfers",
+   """zero or one pangraph file (json, binary or gfa)
+      if no file, reads from stdin
+      stream can be optionally gzipped.""",
+   [
+    Arg(
+        Int,
+        "minimum depth",
+        (short="-d", long="--minimum-depth"),
+        "minimum number of isolates a block must be found in to be compared\n\tblocks found more than once within an isolate are reported but not compared",
+        3,
+    ),
+    Arg(
+        Float64,
+        "core threshold",
+        (short="-c", long="--core-threshold"),
+        "minimum fraction of isolates a single-copy block must be found in to be part of the core genome tree",
+        1.0,
+    ),
+    Arg(
+        Float64,
+        "incongruence threshold",
+        (short="-x", long="--threshold"),
+        "normalized Robinson-Foulds distance above which a block is flagged as incongruent",
+        0.5,
+    ),
+    Arg(
+        String,
+        "output path",
+        (short="-o", long="--output"),
+        "path to store the tab-separated incongruence scores\n\tif empty, the scores are written to stdout",
+        "",
+    ),
+    Arg(
+        String,
+        "tree directory",
+        (short="-t", long="--trees"),
+        "path to directory where the core genome tree and the tree of every compared block will be stored as newick\n\tif empty, will skip this computation",
+        "",
+    ),
+   ],
+
+   function(args)
+       path = parse(Trees, args)
+       path = if (path === nothing || length(path) == 0)
+           nothing
+       elseif length(path) == 1
+           path
+       else
+           usage(Trees)
+           return 2
+       end
+
+       mindepth = arg(Trees, "-d")
+       mindepth ≥ 3 || panic("minimum depth must be at least 3\n")
+
+       core = arg(Trees, "-c")
+       0 < core ≤ 1 || panic("core threshold must lie within (0, 1]\n")
+
+       graph = load(path, Trees)
+       reference, blocks = try
+           Graphs.Phylogeny.incongruence(graph;
+                mindepth  = mindepth,
+                threshold = arg(Trees, "-x"),
+                core      = core,
+           )
+       catch err
+           err isa ErrorException || rethrow()
+           panic("$(err.msg)\n")
+       end
+
+       na(x) = x === nothing ? "NA" : x
+       write_scores = function(io)
+           println(io, join(["block", "length", "isolates", "rf", "score", "incongruent", "status"], '\t'))
+           for b in blocks
+               println(io, join([b.block.uuid, length(b.block), b.isolates, na(b.rf), na(b.score), b.incongruent, b.status], '\t'))
+           end
+       end
+
+       output = arg(Trees, "-o")
+       if length(output) > 0
+           Base.open(write_scores, output, "w")
+       else
+           write_scores(stdout)
+       end
+
+       directory = arg(Trees, "-t")
+       if length(directory) > 0
+           isdir(directory) || mkpath(directory)
+           Base.open("$(directory)/core.nwk", "w") do io
+               println(io, Graphs.newick(reference))
+           end
+           for b in blocks
+               b.tree === nothing && continue
+               Base.open("$(directory)/$(b.block.uuid).nwk", "w") do io
+                   println(io, Graphs.newick(b.tree))
+               end
+           end
+       end
+
+       return 0
+   end
+)
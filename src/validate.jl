@@ -0,0 +1,59 @@
+Validate = Command(
+   "validate",
+   "pangraph validate <options> [pangraph.json] [arguments]",
+   "verifies that a multiple sequence alignment graph reconstructs its input genomes",
+   """one pangraph file (json, binary or gfa), followed by one or more fasta files.
+      files can be optionally gzipped.
+      multiple records within one file are treated as seperate genomes.""",
+   [
+    Arg(
+        Bool,
+        "enforce uppercase",
+        (short="-u", long="--upper-case"),
+        "transforms all input sequence to upper case before comparison",
+        false,
+    ),
+    Arg(
+        String,
+        "report path",
+        (short="-o", long="--output"),
+        "path to store the json report\n\tif empty, the report is written to stdout",
+        "",
+    ),
+   ],
+
+   function(args)
+       files = parse(Validate, args)
+       if files === nothing || length(files) < 2
+           usage(Validate)
+           return 2
+       end
+
+       graph = load(files[1:1], Validate)
+       upper = arg(Validate, "-u")
+
+       reference = Dict{String,Array{UInt8}}()
+       for file in files[2:end]
+           !isfile(file) && error("file '$(file)' not found")
+           open(file) do io
+               for record in read_fasta(io)
+                   reference[record.name] = upper ? UInt8.(uppercase.(Char.(record.seq))) : record.seq
+               end
+           end
+       end
+
+       report = Graphs.validate(graph, reference)
+
+       output = arg(Validate, "-o")
+       if length(output) > 0
+           Base.open(output, "w") do io
+               JSON.print(io, report, 2)
+           end
+       else
+           JSON.print(stdout, report, 2)
+       end
+
+       report.valid || exit(1)
+       return 0
+   end
+)
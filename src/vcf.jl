@@ -0,0 +1,291 @@
+module VCF
+
+import ..Graphs: Graph, Block, Node, alignment, reverse_complement, marshal_vcf, genomelength, copyindex
+
+"""
+    samples(G::Graph; skip=nothing)
+
+Return the sample name associated to each node of graph `G`, along with the ordered list of all sample columns.
+Isolates that pass through every block at most once are named after the isolate.
+Otherwise the `k`th copy of a block within an isolate is named `isolate#k`.
+Isolate `skip`, if given, is excluded.
+"""
+function samples(G::Graph; skip=nothing)
+    name   = Dict{Node{Block},String}()
+    column = String[]
+
+    for isolate in sort(collect(keys(G.sequence)))
+        isolate == skip && continue
+        path   = G.sequence[isolate]
+        copies = copyindex(path)
+        ncopy  = maximum(copies; init=1)
+        for (node, k) in zip(path.node, copies)
+            name[node] = ncopy > 1 ? "$(isolate)#$(k)" : isolate
+        end
+
+        append!(column, ncopy > 1 ? ["$(isolate)#$(k)" for k in 1:ncopy] : [isolate])
+    end
+
+    return name, column
+end
+
+"""
+    struct Record
+        chrom    :: String
+        pos      :: Int
+        ref      :: String
+        alt      :: Array{String,1}
+        kind     :: Symbol
+        genotype :: Dict{String,Int}
+    end
+
+Store a single VCF record.
+`genotype` maps each sample that covers the locus to the index of its allele, 0 being the reference allele.
+Samples not found in `genotype` are reported as missing.
+"""
+struct Record
+    chrom    :: String
+    pos      :: Int
+    ref      :: String
+    alt      :: Array{String,1}
+    kind     :: Symbol
+    genotype :: Dict{String,Int}
+end
+
+"""
+    records(chrom, calls, covered, deleted; len=nothing)
+
+Collapse all `calls`, a map from (position, reference allele, alternate allele, kind) to samples,
+into an array of VCF records sorted by position.
+Alternate alleles that share position, reference allele and kind are reported as one multiallelic record.
+All samples in `covered` that do not carry an alternate allele are reported as reference,
+unless `deleted`, a map from samples to their deleted loci, overlaps the reference allele, in which case they are reported as missing.
+Loci wrap around `len`, if given.
+"""
+function records(chrom, calls, covered, deleted; len=nothing)
+    sites = Dict{Tuple{Int,String,Symbol},Array{String,1}}()
+    for (pos, ref, alt, kind) in keys(calls)
+        push!(get!(sites, (pos, ref, kind), String[]), alt)
+    end
+
+    spanned(sample, pos, ref) = let
+        loci = pos:(pos+length(ref)-1)
+        gaps = get(deleted, sample, BitSet())
+        any((len === nothing ? x : mod1(x, len)) ∈ gaps for x in loci)
+    end
+
+    return sort([
+        let
+            sort!(alts)
+            genotype = Dict{String,Int}(sample => 0 for sample in covered if !spanned(sample, pos, ref))
+            for (i, alt) in enumerate(alts)
+                for sample in calls[(pos, ref, alt, kind)]
+                    genotype[sample] = i
+                end
+            end
+            Record(chrom, pos, ref, alts, kind, genotype)
+        end for ((pos, ref, kind), alts) in sites
+    ]; by=(r)->(r.pos, r.ref, r.kind))
+end
+
+"""
+    variants(b::Block, name)
+
+Return all polymorphisms of block `b` relative to its consensus as VCF records.
+The consensus is used as the chromosome, named by the block `uuid`.
+`name` maps each node of `b` to its sample name.
+Indels are anchored to the preceding consensus nucleotide, or to the following one at the start of the block.
+"""
+function variants(b::Block, name)
+    ref   = b.sequence
+    calls = Dict{Tuple{Int,String,String,Symbol},Array{String,1}}()
+    call! = (key, node) -> push!(get!(calls, key, String[]), name[node])
+
+    for node in keys(b)
+        for (x, nuc) in b.mutate[node]
+            call!((x, string(Char(ref[x])), string(Char(nuc)), :snp), node)
+        end
+
+        for ((x, _), ins) in b.insert[node]
+            length(ins) > 0 || continue
+            if x > 0
+                call!((x, string(Char(ref[x])), string(Char(ref[x]), String(copy(ins))), :ins), node)
+            else
+                call!((1, string(Char(ref[1])), string(String(copy(ins)), Char(ref[1])), :ins), node)
+            end
+        end
+
+        for (x, len) in b.delete[node]
+            if x > 1
+                call!((x-1, String(ref[x-1:x+len-1]), string(Char(ref[x-1])), :del), node)
+            elseif x+len ≤ length(ref)
+                call!((1, String(ref[1:len+1]), string(Char(ref[len+1])), :del), node)
+            end
+            # NOTE: a deletion of the full consensus has no anchor and is not representable
+        end
+    end
+
+    deleted = Dict(name[node] => BitSet(x for (locus, del) in b.delete[node] for x in locus:(locus+del-1)) for node in keys(b))
+    return records(b.uuid, calls, [name[node] for node in keys(b)], deleted)
+end
+
+"""
+    differences(ref, seq)
+
+Return all polymorphisms between two rows of a multiple sequence alignment, `ref` and `seq`.
+Positions are given in the ungapped coordinates of `ref`.
+Indels are anchored to the preceding nucleotide of `ref`, or to the following one at the start of `ref`.
+"""
+function differences(ref, seq)
+    gap   = UInt8('-')
+    nuc   = filter((c) -> c != gap, ref)
+    calls = Tuple{Int,String,String,Symbol}[]
+
+    x, c = 0, 1
+    while c ≤ length(ref)
+        r, s = ref[c], seq[c]
+        if r != gap && s != gap
+            x += 1
+            r != s && push!(calls, (x, string(Char(r)), string(Char(s)), :snp))
+            c += 1
+        elseif r == gap && s == gap
+            c += 1
+        elseif r != gap # deletion
+            del = UInt8[]
+            while c ≤ length(ref) && seq[c] == gap
+                ref[c] != gap && push!(del, ref[c])
+                c += 1
+            end
+
+            if x > 0
+                push!(calls, (x, String(nuc[x:x+length(del)]), string(Char(nuc[x])), :del))
+            elseif length(del) < length(nuc)
+                y = length(del) + 1
+                push!(calls, (1, String(nuc[1:y]), string(Char(nuc[y])), :del))
+            end
+            x += length(del)
+        else # insertion
+            ins = UInt8[]
+            while c ≤ length(ref) && ref[c] == gap
+                seq[c] != gap && push!(ins, seq[c])
+                c += 1
+            end
+
+            if x > 0
+                push!(calls, (x, string(Char(nuc[x])), string(Char(nuc[x]), String(ins)), :ins))
+            elseif length(nuc) > 0
+                push!(calls, (1, string(Char(nuc[1])), string(String(ins), Char(nuc[1])), :ins))
+            end
+        end
+    end
+
+    return calls
+end
+
+# ungapped loci of alignment row `ref` that are deleted within row `seq`
+function deletions(ref, seq)
+    gap  = UInt8('-')
+    loci = BitSet()
+    x    = 0
+    for (r, s) in zip(ref, seq)
+        r == gap && continue
+        x += 1
+        s == gap && push!(loci, x)
+    end
+    return loci
+end
+
+"""
+    project(G::Graph, reference, name)
+
+Return all polymorphisms of graph `G` relative to isolate `reference` as VCF records in the linear coordinates of `reference`.
+Only blocks shared with `reference` are considered; each copy of a block within `reference` is projected independently.
+`name` maps each node of `G` to its sample name.
+"""
+function project(G::Graph, reference, name)
+    path = G.sequence[reference]
+    self = Set(path.node)
+    len  = genomelength(path)
+
+    variants = Record[]
+    for (i, node) in enumerate(path.node)
+        others = [n for n in keys(node.block) if n ∉ self]
+        length(others) > 0 || continue
+
+        aln, nodes, _ = alignment(node.block)
+        row = (n) -> let
+            seq = aln[:, findfirst((m) -> m === n, nodes)]
+            node.strand ? seq : reverse_complement(seq)
+        end
+
+        ref     = row(node)
+        calls   = Dict{Tuple{Int,String,String,Symbol},Array{String,1}}()
+        deleted = Dict{String,BitSet}()
+        locus   = (x) -> mod(path.position[i] + x - 2, len) + 1
+        for n in others
+            seq = row(n)
+            for (x, r, a, kind) in differences(ref, seq)
+                push!(get!(calls, (locus(x), r, a, kind), String[]), name[n])
+            end
+            deleted[name[n]] = BitSet(locus(x) for x in deletions(ref, seq))
+        end
+
+        append!(variants, records(reference, calls, [name[n] for n in others], deleted; len=len))
+    end
+
+    return sort(variants; by=(r)->(r.pos, r.ref, r.kind))
+end
+
+function header(io::IO, contigs, column)
+    write(io, "##fileformat=VCFv4.2\n")
+    write(io, "##source=pangraph\n")
+    for (id, len) in contigs
+        write(io, "##contig=<ID=$(id),length=$(len)>\n")
+    end
+    write(io, "##INFO=<ID=TYPE,Number=1,Type=String,Description=\"Type of polymorphism: snp, ins or del\">\n")
+    write(io, "##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">\n")
+    write(io, join(["#CHROM","POS","ID","REF","ALT","QUAL","FILTER","INFO","FORMAT",column...], '\t'), '\n')
+end
+
+function emit(io::IO, r::Record, column)
+    genotype = [sample ∈ keys(r.genotype) ? string(r.genotype[sample]) : "." for sample in column]
+    write(io, join([r.chrom, r.pos, ".", r.ref, join(r.alt, ','), ".", "PASS", "TYPE=$(r.kind)", "GT", genotype...], '\t'), '\n')
+end
+
+"""
+    marshal_vcf(io::IO, G::Graph; opt=nothing)
+
+Output all polymorphisms of pangraph `G` as a multi-sample VCF to IO stream `io`.
+Each block is reported as a chromosome, named by its `uuid`, whose reference is the block consensus.
+Each isolate, or each copy of a block within an isolate if duplicated, is reported as a haploid sample.
+
+If `opt` contains the field `reference`, polymorphisms are instead projected onto the linear coordinates of the named isolate.
+The reference isolate is then excluded from the samples.
+"""
+function marshal_vcf(io::IO, G::Graph; opt=nothing)
+    if opt !== nothing && hasproperty(opt, :reference)
+        reference = opt.reference
+        reference ∈ keys(G.sequence) || error("'$(reference)' not a valid sequence identifier")
+
+        name, column = samples(G; skip=reference)
+        len = genomelength(G.sequence[reference])
+
+        header(io, [(reference, len)], column)
+        for r in project(G, reference, name)
+            emit(io, r, column)
+        end
+        return
+    end
+
+    name, column = samples(G)
+    blocks = sort(collect(values(G.block)); by=(b)->b.uuid)
+
+    header(io, [(b.uuid, length(b)) for b in blocks], column)
+    for b in blocks
+        for r in variants(b, name)
+            emit(io, r, column)
+        end
+    end
+end
+
+end
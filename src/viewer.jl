@@ -0,0 +1,99 @@
+module Viewer
+
+import JSON
+import ..Graphs: Graph, Block, depth, diversity, marshal_html, genomelength
+
+export color
+
+# NOTE: the template is read at compile time so that the viewer is embedded within compiled binaries
+const TEMPLATE = read(joinpath(@__DIR__, "static", "viewer.html"), String)
+include_dependency(joinpath(@__DIR__, "static", "viewer.html"))
+
+"""
+    color(uuid)
+
+Return the color of the block identified by `uuid` as a hexadecimal RGB string.
+Colors only depend on the identifier and are thus consistent across figures and runs.
+"""
+function color(uuid)
+    # 32-bit FNV-1a hash
+    h = 0x811c9dc5
+    for c in codeunits(uuid)
+        h = (h ⊻ UInt32(c)) * 0x01000193
+    end
+
+    hue = (h % 360) / 60
+    sat = 0.45 + 0.3*((h >> 9) % 100)/100
+    val = 0.70 + 0.25*((h >> 17) % 100)/100
+
+    # hsv -> rgb
+    c = val*sat
+    x = c*(1 - abs(hue % 2 - 1))
+    r, g, b = if hue < 1
+        (c, x, 0)
+    elseif hue < 2
+        (x, c, 0)
+    elseif hue < 3
+        (0, c, x)
+    elseif hue < 4
+        (0, x, c)
+    elseif hue < 5
+        (x, 0, c)
+    else
+        (c, 0, x)
+    end
+    m = val - c
+
+    return "#" * join(string(round(Int, 255*(v+m)); base=16, pad=2) for v in (r, g, b))
+end
+
+"""
+    marshal_html(io::IO, G::Graph; opt=nothing)
+
+Serialize graph `G` as a self-contained, interactive HTML page to IO stream `io`.
+Each isolate is drawn as a linear track of blocks, colored by block identifier and pointing along their strand, in the original rotation of circular genomes.
+Homologous blocks of adjacent tracks are connected by ribbons; hovering a block shows its length, depth and diversity.
+The graph is embedded as JSON, thus the page requires no network access.
+
+`opt` may be a named tuple with field `title`, used as the title of the page.
+"""
+function marshal_html(io::IO, G::Graph; opt=nothing)
+    blocks = sort(collect(values(G.block)); by=(b)->b.uuid)
+    # NOTE: javascript arrays are 0-based
+    index  = Dict(b => i-1 for (i, b) in enumerate(blocks))
+
+    data = (
+        blocks = [
+            (
+                id        = b.uuid,
+                length    = length(b),
+                depth     = depth(b),
+                diversity = diversity(b),
+                color     = color(b.uuid),
+            ) for b in blocks
+        ],
+        isolates = [
+            let p = G.sequence[name]
+                (
+                    name     = name,
+                    length   = genomelength(p),
+                    circular = p.circular,
+                    nodes    = [
+                        (index[node.block], node.strand, p.position[i], length(node))
+                        for (i, node) in enumerate(p.node) if length(node) > 0
+                    ],
+                )
+            end for name in sort(collect(keys(G.sequence)))
+        ],
+    )
+
+    title = (opt !== nothing && haskey(opt, :title)) ? opt.title : "pangraph"
+    html  = replace(TEMPLATE,
+        "{{TITLE}}" => replace(title, "&" => "&amp;", "<" => "&lt;", ">" => "&gt;"),
+        # NOTE: prevent block identifiers or isolate names from closing the embedding script tag
+        "{{DATA}}"  => replace(JSON.json(data), "</" => "<\\/"),
+    )
+    write(io, html)
+end
+
+end
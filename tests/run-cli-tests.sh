@@ -1,6 +1,9 @@
 #!/bin/bash
 set -euxo pipefail
 
+# fasta records, one per line, in sorted order
+records() { awk '/^>/ { if (id) print id "\t" seq; id = $1; seq = ""; next } { seq = seq toupper($0) } END { print id "\t" seq }' "$1" | sort; }
+
 # test that mash, mafft, mmseqs and fasttree are available in path
 echo "mash version:"
 mash --version
@@ -16,6 +19,18 @@ pangraph help build
 pangraph help polish
 pangraph help export
 pangraph help marginalize
+pangraph help add
+pangraph help merge
+pangraph help validate
+pangraph help sequences
+pangraph help extract
+pangraph help liftover
+pangraph help annotate
+pangraph help serve
+pangraph help plot
+pangraph help stats
+pangraph help growth
+pangraph help trees
 
 # create input data
 TESTDIR="tests/data"
@@ -40,6 +55,18 @@ pangraph help version
 pangraph help export
 pangraph help marginalize
 pangraph help polish
+pangraph help add
+pangraph help merge
+pangraph help validate
+pangraph help sequences
+pangraph help extract
+pangraph help liftover
+pangraph help annotate
+pangraph help serve
+pangraph help plot
+pangraph help stats
+pangraph help growth
+pangraph help trees
 
 echo "Test pangraph version"
 pangraph version
@@ -58,11 +85,162 @@ echo "Test pangraph build - mmseqs"
 export JULIA_NUM_THREADS=1
 pangraph build -c -k mmseqs -K 8 "$TESTDIR/input.fa" > "$TESTDIR/test3.json"
 
+echo "Test pangraph build - guide tree"
+pangraph build -c -T "$TESTDIR/guide.nwk" "$TESTDIR/input.fa" > "$TESTDIR/guided.json"
+pangraph build -c -t "$TESTDIR/guide.nwk" "$TESTDIR/input.fa" > "$TESTDIR/guided.json"
+
+echo "Test pangraph add"
+sed 's/^>/>new_/' "$TESTDIR/randseqs.fa" > "$TESTDIR/new.fa"
+pangraph add -c "$TESTDIR/test1.json" "$TESTDIR/new.fa" > "$TESTDIR/added.json"
+# a genome without homology to the graph leaves all existing blocks untouched
+awk 'BEGIN { srand(7); print ">unrelated"; for (i = 0; i < 20000; i++) printf "%s", substr("ACGT", int(4*rand()) + 1, 1); print "" }' > "$TESTDIR/unrelated.fa"
+pangraph add -c "$TESTDIR/test1.json" "$TESTDIR/unrelated.fa" > "$TESTDIR/unrelated.json"
+julia --project=. -e "using JSON; \
+    before = JSON.parsefile(\"$TESTDIR/test1.json\"); after = JSON.parsefile(\"$TESTDIR/unrelated.json\"); \
+    uuids  = Set(blk[\"id\"] for blk in before[\"blocks\"]); \
+    added  = Set(node[\"id\"] for path in after[\"paths\"] if path[\"name\"] == \"unrelated\" for node in path[\"blocks\"]); \
+    issubset(uuids, Set(blk[\"id\"] for blk in after[\"blocks\"])) && isdisjoint(uuids, added) || exit(1)"
+
+echo "Test pangraph merge"
+pangraph build -c "$TESTDIR/new.fa" > "$TESTDIR/new.json"
+pangraph merge "$TESTDIR/test1.json" "$TESTDIR/new.json" > "$TESTDIR/merged.json"
+
+echo "Test pangraph binary format"
+pangraph build -c -f binary "$TESTDIR/input.fa" > "$TESTDIR/test1.pgb"
+pangraph export -ng -pa -p binary -o "$TESTDIR/export" "$TESTDIR/test1.pgb"
+pangraph polish -c -l 10000 -f binary "$TESTDIR/test1.pgb" > "$TESTDIR/polished.pgb"
+pangraph sequences "$TESTDIR/test1.pgb" > "$TESTDIR/binary.fa"
+diff <(records "$TESTDIR/input.fa") <(records "$TESTDIR/binary.fa")
+# polishing blocks of length 0 realigns nothing and only converts between formats
+pangraph polish -l 0 "$TESTDIR/test1.pgb" > "$TESTDIR/binary.json"
+pangraph polish -l 0 -f binary "$TESTDIR/binary.json" > "$TESTDIR/roundtrip.pgb"
+pangraph polish -l 0 "$TESTDIR/roundtrip.pgb" > "$TESTDIR/roundtrip.json"
+julia --project=. -e "using JSON; \
+    uuids(file) = sort([blk[\"id\"] for blk in JSON.parsefile(file)[\"blocks\"]]); \
+    uuids(\"$TESTDIR/binary.json\") == uuids(\"$TESTDIR/roundtrip.json\") || exit(1)"
+
+echo "Test pangraph input format detection"
+if pangraph stats "$TESTDIR/input.fa" > /dev/null; then exit 1; fi
+
+echo "Test pangraph schema migration"
+julia --project=. -e "using JSON; \
+    graph = JSON.parsefile(\"$TESTDIR/test1.json\"); \
+    delete!(graph, \"version\"); delete!(graph, \"metadata\"); \
+    foreach(path -> delete!(path, \"position\"), graph[\"paths\"]); \
+    open(io -> JSON.print(io, graph), \"$TESTDIR/unversioned.json\", \"w\")"
+pangraph export -ng -pa -p unversioned -o "$TESTDIR/export" "$TESTDIR/unversioned.json"
+
+echo "Test pangraph validate"
+pangraph validate -o "$TESTDIR/validate.json" "$TESTDIR/test1.json" "$TESTDIR/input.fa"
+awk 'NR == 2 { $0 = (substr($0, 1, 1) == "A" ? "C" : "A") substr($0, 2) } { print }' "$TESTDIR/input.fa" > "$TESTDIR/corrupt.fa"
+if pangraph validate -o "$TESTDIR/invalid.json" "$TESTDIR/test1.json" "$TESTDIR/corrupt.fa"; then exit 1; fi
+
+echo "Test pangraph sequences"
+pangraph sequences "$TESTDIR/test1.json" > "$TESTDIR/sequences.fa"
+diff <(records "$TESTDIR/input.fa") <(records "$TESTDIR/sequences.fa")
+pangraph sequences -n -s isolate_1,isolate_2 -o "$TESTDIR/sequences" "$TESTDIR/test1.json"
+
+echo "Test pangraph extract"
+pangraph extract -i isolate_1 -r 1000-5000 -n 500 "$TESTDIR/test1.json" > "$TESTDIR/region.json"
+pangraph export -ng -pa -p region -o "$TESTDIR/export" "$TESTDIR/region.json"
+
+echo "Test pangraph liftover"
+pangraph liftover -s isolate_1 -p 1,1000,20000 "$TESTDIR/test1.json" > "$TESTDIR/liftover.tsv"
+pangraph liftover -s isolate_1 -t isolate_2,isolate_3 -p 5000 "$TESTDIR/test1.json" > "$TESTDIR/liftover_subset.tsv"
+if pangraph liftover -s isolate_1 -p 0 "$TESTDIR/test1.json" > /dev/null; then exit 1; fi
+
+echo "Test pangraph annotate"
+printf '##gff-version 3\nisolate_1\ttest\tgene\t100\t1500\t.\t+\t.\tID=gene_1;Name=a\nisolate_1\ttest\tCDS\t3000\t4200\t.\t-\t0\tID=cds_1\n' > "$TESTDIR/annotation.gff3"
+pangraph annotate -g "$TESTDIR/annotation.gff3" -o "$TESTDIR/annotation" "$TESTDIR/test1.json" > "$TESTDIR/annotated.json"
+pangraph export -ng -pa -p annotated -o "$TESTDIR/export" "$TESTDIR/annotated.json"
+pangraph marginalize -s isolate_1,isolate_2 -f binary "$TESTDIR/annotated.json" > "$TESTDIR/annotated_marginal.pgb"
+pangraph export -ng -pa -p annotated_marginal -o "$TESTDIR/export" "$TESTDIR/annotated_marginal.pgb"
+pangraph add -c "$TESTDIR/annotated.json" "$TESTDIR/new.fa" > "$TESTDIR/annotated_added.json"
+pangraph export -ng -pa -p annotated_added -o "$TESTDIR/export" "$TESTDIR/annotated_added.json"
+
+echo "Test pangraph serve"
+pangraph serve -p 18080 "$TESTDIR/test1.json" &
+SERVER=$!
+curl -sf --retry 60 --retry-delay 2 --retry-connrefused "http://127.0.0.1:18080/isolates" > "$TESTDIR/serve_isolates.json"
+BLOCK=$(curl -sf "http://127.0.0.1:18080/paths/isolate_1" | grep -o '"id":"[A-Z]*"' | head -n 1 | cut -d '"' -f 4)
+curl -sf "http://127.0.0.1:18080/blocks/$BLOCK" > "$TESTDIR/serve_block.json"
+curl -sf "http://127.0.0.1:18080/blocks/$BLOCK/alignment" > "$TESTDIR/serve_alignment.json"
+curl -sf "http://127.0.0.1:18080/liftover?from=isolate_1&position=1000" > "$TESTDIR/serve_liftover.json"
+curl -sf "http://127.0.0.1:18080/region?isolate=isolate_1&start=1000&end=5000" > "$TESTDIR/serve_region.json"
+test "$(curl -s -o /dev/null -w '%{http_code}' "http://127.0.0.1:18080/blocks/missing")" = "404"
+kill $SERVER
+
+echo "Test pangraph plot"
+pangraph plot -o "$TESTDIR/plot.svg" "$TESTDIR/test1.json"
+echo "((isolate_2:0.1,isolate_1:0.1):0.05,isolate_3:0.2);" > "$TESTDIR/plot.nwk"
+pangraph plot -s isolate_1,isolate_2,isolate_3 -t "$TESTDIR/plot.nwk" -ll 500 -dl 2 "$TESTDIR/test1.json" > "$TESTDIR/plot_tree.svg"
+
+echo "Test pangraph stats"
+pangraph stats "$TESTDIR/test1.json" > "$TESTDIR/stats.json"
+pangraph stats -f tsv -c 0.95 -C 0.2 -o "$TESTDIR/stats.tsv" "$TESTDIR/test1.json"
+
+echo "Test pangraph growth"
+pangraph growth -n 5 -r 42 -H "$TESTDIR/heaps.json" "$TESTDIR/test1.json" > "$TESTDIR/growth.tsv"
+
+echo "Test pangraph trees"
+pangraph trees -t "$TESTDIR/trees" "$TESTDIR/test1.json" > "$TESTDIR/incongruence.tsv"
+pangraph trees -c 0.8 -d 4 "$TESTDIR/test1.json" > "$TESTDIR/incongruence_relaxed.tsv"
+
 echo "Test pangraph polish"
 pangraph polish -c -l 10000 "$TESTDIR/test1.json" > "$TESTDIR/polished.json"
 
 echo "Test pangraph GFA export"
 pangraph export -o "$TESTDIR/export" "$TESTDIR/test1.json"
+pangraph export -gv 2.0 -p gfa2 -o "$TESTDIR/export" "$TESTDIR/test1.json"
+# genomes that share their blocks without polymorphisms, such that walks spell the genomes exactly
+awk '/^>/ { n++; next } n == 1 { seq = seq toupper($0) } END {
+    a = substr(seq, 1, 3000); b = substr(seq, 3001, 150); c = substr(seq, 3151, 4000); d = substr(seq, 7151, 2000)
+    print ">shuffled_1"; print a b c d
+    print ">shuffled_2"; print a c b d
+    print ">shuffled_3"; print substr(a b c d, 1001) substr(a b c d, 1, 1000)
+}' "$TESTDIR/input.fa" > "$TESTDIR/shuffled.fa"
+pangraph build -c "$TESTDIR/shuffled.fa" > "$TESTDIR/shuffled.json"
+pangraph export -ell 500 -gv 1.1 -p shuffled -o "$TESTDIR/export" "$TESTDIR/shuffled.json"
+awk -F '\t' '
+    $1 == "S" { len[$2] = length($3) }
+    $1 == "W" {
+        walk = $7; gsub(/[<>]/, " ", walk); split(walk, steps, " ")
+        n = 0; for (i in steps) n += len[steps[i]]
+        shift = 0; for (i = 8; i <= NF; i++) if ($i ~ /^OF:i:/) shift = substr($i, 6)
+        if (n != $6 - $5 + shift) { print "walk of " $2 " spells " n " nucleotides, expected " ($6 - $5 + shift); exit 1 }
+    }' "$TESTDIR/export/shuffled.gfa"
+
+echo "Test pangraph GFA import"
+pangraph export -ng -pa -p imported -o "$TESTDIR/export" "$TESTDIR/export/pangraph.gfa"
+pangraph marginalize -o "$TESTDIR/marginalize_gfa" "$TESTDIR/export/pangraph.gfa"
+pangraph export -ell 0 -p unfiltered -o "$TESTDIR/export" "$TESTDIR/shuffled.json"
+pangraph sequences "$TESTDIR/export/unfiltered.gfa" > "$TESTDIR/unfiltered.fa"
+diff <(records "$TESTDIR/shuffled.fa") <(records "$TESTDIR/unfiltered.fa")
+
+echo "Test pangraph marginalize matrices"
+pangraph marginalize -m "$TESTDIR/matrices" "$TESTDIR/test1.json"
+pangraph marginalize -m "$TESTDIR/matrices" -mf phylip "$TESTDIR/test1.json"
+
+echo "Test pangraph VCF export"
+pangraph export -ng -vcf -o "$TESTDIR/export" "$TESTDIR/test1.json"
+pangraph export -ng -vr isolate_1 -o "$TESTDIR/export" "$TESTDIR/test1.json"
+
+echo "Test pangraph BED export"
+pangraph export -ng -bed -o "$TESTDIR/export" "$TESTDIR/test1.json"
+
+echo "Test pangraph HTML export"
+pangraph export -ng -html -o "$TESTDIR/export" "$TESTDIR/test1.json"
+
+echo "Test pangraph core alignment export"
+pangraph export -ng -ca -o "$TESTDIR/export" "$TESTDIR/test1.json"
+pangraph export -ng -ca -cs -ct 0.5 -cf phylip -o "$TESTDIR/export" "$TESTDIR/test1.json"
+
+echo "Test pangraph presence/absence export"
+pangraph export -ng -pa -paf csv -o "$TESTDIR/export" "$TESTDIR/test1.json"
+
+echo "Test pangraph block alignment export"
+pangraph export -ng -ba "$TESTDIR/export/blocks" -maf -o "$TESTDIR/export" "$TESTDIR/test1.json"
+pangraph export -ng -ba "$TESTDIR/export/blocks" -baf stockholm -o "$TESTDIR/export" "$TESTDIR/test1.json"
 
 echo "Test pangraph PanX export"
 pangraph export -ng -pX -o "$TESTDIR/export" "$TESTDIR/test1.json"
@@ -13,6 +13,18 @@ PanGraph.main(["help", "marginalize"]) # marginalize usage
 PanGraph.main(["help", "polish"])      # polish usage
 PanGraph.main(["help", "version"])		   # version usage
 PanGraph.main(["help", "help"])		   # help usage
+PanGraph.main(["help", "add"])         # add usage
+PanGraph.main(["help", "merge"])       # merge usage
+PanGraph.main(["help", "validate"])    # validate usage
+PanGraph.main(["help", "sequences"])   # sequences usage
+PanGraph.main(["help", "extract"])     # extract usage
+PanGraph.main(["help", "liftover"])    # liftover usage
+PanGraph.main(["help", "annotate"])    # annotate usage
+PanGraph.main(["help", "serve"])       # serve usage
+PanGraph.main(["help", "plot"])        # plot usage
+PanGraph.main(["help", "stats"])       # stats usage
+PanGraph.main(["help", "growth"])      # growth usage
+PanGraph.main(["help", "trees"])       # trees usage
 
 # build (native - mmseqs)
 PanGraph.main(["build", "-c", "-u", "-b", "0", "-a", "0", "$root/test.fa"])